@@ -1,17 +1,21 @@
 use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use std::sync::Arc;
 use wordlehelper::analyze::analyzer;
+use wordlehelper::analyze::pattern_matrix::PatternMatrix;
 use wordlehelper::guess::guesses::GuessGrid;
 use wordlehelper::guess::known_word_constraints::{CharKnowledge, KnownWordConstraints};
 use wordlehelper::word_list::WordList;
 
 fn bench_analyzers(c: &mut Criterion) {
     let mut group = c.benchmark_group("analyzers");
-    let words_5c: WordList<5> = WordList::std();
+    let words_5c: WordList<5> = WordList::std().unwrap();
+    let matrix = PatternMatrix::build(&words_5c).expect("couldn't build pattern matrix");
+    let words_5c = words_5c.with_pattern_matrix(Arc::new(matrix));
     for analyzer in analyzer::standard_suite() {
         group.bench_with_input(
             BenchmarkId::new("filter", analyzer.name()),
             &words_5c,
-            |b, words| b.iter(|| analyzer.analyze(words)),
+            |b, words| b.iter(|| analyzer.analyze(words, words)),
         );
     }
 }
@@ -21,8 +25,8 @@ fn bench_filter(c: &mut Criterion) {
 
     for size in [10, 100, 1_000, 2_000, 2_500, 5_000] {
         group.throughput(Throughput::Elements(size));
-        let words_5c: WordList<5> = WordList::std();
-        let mut grid = GuessGrid::<5, 1>::new();
+        let words_5c: WordList<5> = WordList::std().unwrap();
+        let mut grid = GuessGrid::<5>::new(1);
         let row_knowledge = [
             ('a', CharKnowledge::WrongPosition),
             ('b', CharKnowledge::Missing),
@@ -1,3 +1,5 @@
+use crate::analyze::entropy::EntropyAnalyzer;
+use crate::analyze::minimax::MinimaxAnalyzer;
 use crate::analyze::pattern::PatternBasedAnalyzer;
 use crate::analyze::position_chars::CharPositionScorer;
 use crate::analyze::scored_chars::CharScorer;
@@ -5,9 +7,27 @@ use crate::analyze::simple_analyzers::{CharFrequencies, Random, WordFrequencies}
 use crate::word_list::WordList;
 use std::cmp::Ordering;
 
-pub trait Analyzer<const N: usize> {
+/// Analyzers must be `Send` and `Sync`, so that the auto-guesser can run many games in parallel.
+pub trait Analyzer<const N: usize>: Send + Sync {
     fn name(&self) -> String;
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>>;
+    /// Scores words to guess next, given that the answer is one of `answers`.
+    ///
+    /// `guesses` is the full dictionary of allowed guesses, including words that have already been
+    /// ruled out as answers. Analyzers that can make use of such "probe" words may score them;
+    /// others may just score the `answers`.
+    fn analyze<'a>(
+        &self,
+        guesses: &'a WordList<N>,
+        answers: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>>;
+
+    /// Whether this analyzer gets its patterns from the answers' `PatternMatrix`, if there is one.
+    /// If none of the analyzers do, there's no point in building it.
+    ///
+    /// [`PatternMatrix`]: crate::analyze::pattern_matrix::PatternMatrix
+    fn uses_pattern_matrix(&self) -> bool {
+        false
+    }
 }
 
 pub fn standard_suite<const N: usize>() -> Vec<Box<dyn Analyzer<N>>> {
@@ -23,11 +43,49 @@ pub fn standard_suite<const N: usize>() -> Vec<Box<dyn Analyzer<N>>> {
         }),
         Box::new(WordFrequencies {}),
         Box::new(CharPositionScorer {}),
-        Box::new(PatternBasedAnalyzer { limit: 3500 }),
+        Box::new(PatternBasedAnalyzer {}),
+        Box::new(EntropyAnalyzer {
+            weight_by_freq: false,
+        }),
+        Box::new(EntropyAnalyzer {
+            weight_by_freq: true,
+        }),
+        Box::new(MinimaxAnalyzer {}),
         Box::new(Random {}),
     ]
 }
 
+/// Picks analyzers from the `standard_suite()` by name. Each query selects every analyzer whose name
+/// contains it, case-insensitively; for example, `"entropy"` selects all of the entropy-based
+/// analyzers. If there are no queries, this returns the whole suite.
+pub fn select_from_standard_suite<const N: usize>(
+    queries: &[String],
+) -> Result<Vec<Box<dyn Analyzer<N>>>, String> {
+    let suite = standard_suite();
+    if queries.is_empty() {
+        return Ok(suite);
+    }
+    let queries: Vec<String> = queries.iter().map(|q| q.to_lowercase()).collect();
+    if let Some(unmatched) = queries
+        .iter()
+        .find(|q| !suite.iter().any(|a| a.name().to_lowercase().contains(*q)))
+    {
+        let names: Vec<String> = suite.iter().map(|a| a.name()).collect();
+        return Err(format!(
+            "no analyzer matches \"{}\". Available analyzers: {}",
+            unmatched,
+            names.join("; ")
+        ));
+    }
+    Ok(suite
+        .into_iter()
+        .filter(|a| {
+            let name = a.name().to_lowercase();
+            queries.iter().any(|q| name.contains(q))
+        })
+        .collect())
+}
+
 #[derive(PartialEq)]
 pub struct ScoredWord<'a> {
     pub word: &'a str,
@@ -1,116 +1,376 @@
 use crate::analyze::analyzer::{Analyzer, ScoredWord};
 use crate::analyze::auto_guesser::GuessResult::{Failure, Success};
 use crate::guess::guesses::{GuessGrid, GuessStr};
-use crate::guess::known_word_constraints::{CharKnowledge, KnownWordConstraints};
+use crate::guess::host::{Absurdle, FixedAnswer, Host};
+use crate::guess::known_word_constraints::KnownWordConstraints;
 use crate::word_list::WordList;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::Serialize;
 use strum::Display;
 
-use crate::analyze::util;
-
-pub struct AutoGuesser<const N: usize, const R: usize> {
+pub struct AutoGuesser<const N: usize> {
+    /// The answers to try to guess; each one is a separate game.
     pub answer_words: Vec<String>,
-    pub words_list: WordList<N>,
+    /// All of the words we're allowed to guess.
+    pub guesses_list: WordList<N>,
+    /// All of the words that might be the answer. This gets filtered down as we guess.
+    pub answers_list: WordList<N>,
     pub analyzers: Vec<Box<dyn Analyzer<N>>>,
+    /// How many guesses each game gets before it counts as a failure.
+    pub rows: usize,
+    /// Whether to play by hard mode's rules: every guess must use all of the hints revealed so far.
+    pub hard_mode: bool,
+    /// How many threads to spread the games across. 0 means one per core.
+    pub threads: usize,
 }
 
-#[derive(Display)]
+#[derive(Display, Serialize)]
 pub enum GuessResult {
     Success,
     Failure,
 }
 
+#[derive(Serialize)]
 pub struct AnalyzerGuessResult<const N: usize> {
     pub name: String,
     pub result: GuessResult,
     pub guesses: Vec<GuessStr<N>>,
 }
 
+#[derive(Serialize)]
 pub struct ResultsByWord<const N: usize> {
     pub answer: String,
     pub analyzer_results: Vec<AnalyzerGuessResult<N>>,
 }
 
-impl<const N: usize, const R: usize> AutoGuesser<N, R> {
-    pub fn guess_all(self) -> Vec<ResultsByWord<N>> {
-        let mut all_results = Vec::with_capacity(self.answer_words.len());
-        for answer in self.answer_words {
-            if answer.len() != N {
-                continue;
-            }
-            let mut results_by_analyzer = Vec::with_capacity(self.analyzers.len());
-            for analyzer in &self.analyzers {
-                let (result, guesses) =
-                    Self::guess_one(&self.words_list, &answer, analyzer.as_ref());
-                results_by_analyzer.push(AnalyzerGuessResult {
-                    name: analyzer.name().to_string(), // TODO can borrow, with some lifetime trickery
-                    result,
-                    guesses,
-                });
-            }
-            all_results.push(ResultsByWord {
+impl<const N: usize> AutoGuesser<N> {
+    /// Plays every answer against every analyzer. The games run in parallel, but the results are
+    /// always in the same order: by answer (in the order given), then by analyzer. Fails if any
+    /// answer doesn't have `N` letters.
+    pub fn guess_all(self) -> Result<Vec<ResultsByWord<N>>, String> {
+        if let Some(bad) = self
+            .answer_words
+            .iter()
+            .find(|answer| answer.chars().count() != N)
+        {
+            return Err(format!(
+                "expected {}-letter answers, but got \"{}\"",
+                N, bad
+            ));
+        }
+        let pool = self.thread_pool()?;
+        let answers = self.answer_words;
+        let games: Vec<(&str, &dyn Analyzer<N>)> = answers
+            .iter()
+            .flat_map(|answer| {
+                self.analyzers
+                    .iter()
+                    .map(move |analyzer| (answer.as_str(), analyzer.as_ref()))
+            })
+            .collect();
+
+        // par_iter's collect preserves the original order, regardless of which thread finished
+        // first.
+        let game_results: Vec<AnalyzerGuessResult<N>> = pool.install(|| {
+            games
+                .par_iter()
+                .map(|&(answer, analyzer)| {
+                    let (result, guesses) = Self::guess_one(
+                        &self.guesses_list,
+                        &self.answers_list,
+                        answer,
+                        analyzer,
+                        self.rows,
+                        self.hard_mode,
+                    );
+                    AnalyzerGuessResult {
+                        name: analyzer.name().to_string(), // TODO can borrow, with some lifetime trickery
+                        result,
+                        guesses,
+                    }
+                })
+                .collect()
+        });
+
+        let mut game_results = game_results.into_iter();
+        Ok(answers
+            .into_iter()
+            .map(|answer| ResultsByWord {
                 answer,
-                analyzer_results: results_by_analyzer,
+                analyzer_results: game_results.by_ref().take(self.analyzers.len()).collect(),
             })
-        }
-        all_results
+            .collect())
+    }
+
+    /// Plays each analyzer against an adversarial host (see `Absurdle`), instead of against
+    /// `answer_words`, which are ignored. The host picks its answer as it goes, to make each game
+    /// as long as it can be; so each analyzer plays just one game, and the results are in the
+    /// same order as the analyzers. Each result's answer is the one the host ended up with.
+    pub fn guess_adversarial(self) -> Result<Vec<ResultsByWord<N>>, String> {
+        // Make sure there's something to pick from, before starting any games.
+        Absurdle::new(&self.answers_list)?;
+        let pool = self.thread_pool()?;
+        let results = pool.install(|| {
+            self.analyzers
+                .par_iter()
+                .map(|analyzer| {
+                    let mut host =
+                        Absurdle::new(&self.answers_list).expect("checked that it's not empty");
+                    let (result, guesses) = Self::guess_against(
+                        &self.guesses_list,
+                        &self.answers_list,
+                        &mut host,
+                        analyzer.as_ref(),
+                        self.rows,
+                        self.hard_mode,
+                    );
+                    ResultsByWord {
+                        answer: Host::<N>::answer(&host).to_string(),
+                        analyzer_results: vec![AnalyzerGuessResult {
+                            name: analyzer.name().to_string(),
+                            result,
+                            guesses,
+                        }],
+                    }
+                })
+                .collect()
+        });
+        Ok(results)
+    }
+
+    fn thread_pool(&self) -> Result<ThreadPool, String> {
+        ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build()
+            .map_err(|e| format!("couldn't start {} threads: {}", self.threads, e))
     }
 
     pub fn guess_one(
-        words_list: &WordList<N>,
+        guesses_list: &WordList<N>,
+        answers_list: &WordList<N>,
         answer: &str,
         analyzer: &dyn Analyzer<N>,
+        rows: usize,
+        hard_mode: bool,
     ) -> (GuessResult, Vec<GuessStr<N>>) {
-        let mut grid = GuessGrid::<N, R>::new();
-        let mut possible_words = words_list.filter_preview(&KnownWordConstraints::empty());
-        let answer_upper = answer.to_ascii_uppercase();
-        for guess_num in 0..R {
-            possible_words.filter(&KnownWordConstraints::from_grid(&grid));
-            let mut scores: Vec<ScoredWord> = analyzer.analyze(&possible_words);
+        Self::guess_against(
+            guesses_list,
+            answers_list,
+            &mut FixedAnswer::new(answer),
+            analyzer,
+            rows,
+            hard_mode,
+        )
+    }
+
+    /// Plays one game with `analyzer`, with `host` scoring each guess.
+    pub fn guess_against(
+        guesses_list: &WordList<N>,
+        answers_list: &WordList<N>,
+        host: &mut dyn Host<N>,
+        analyzer: &dyn Analyzer<N>,
+        rows: usize,
+        hard_mode: bool,
+    ) -> (GuessResult, Vec<GuessStr<N>>) {
+        let mut grid = GuessGrid::<N>::new(rows);
+        let mut possible_words = answers_list.filter_preview(&KnownWordConstraints::empty());
+        for guess_num in 0..rows {
+            let constraints = KnownWordConstraints::from_grid(&grid);
+            possible_words.filter(&constraints);
+            let hard_mode_guesses;
+            let allowed_guesses = if hard_mode {
+                hard_mode_guesses = guesses_list.filter_preview(&constraints.hard_mode());
+                &hard_mode_guesses
+            } else {
+                guesses_list
+            };
+            let mut scores: Vec<ScoredWord> = analyzer.analyze(allowed_guesses, &possible_words);
             scores.sort();
-            let Some(&ScoredWord{word: best_guess, ..}) = scores.first() else {
+            let Some(&ScoredWord {
+                word: best_guess, ..
+            }) = scores.first()
+            else {
+                return (Failure, grid.into_iter().take(guess_num).collect());
+            };
+            let Ok(row) = host.score(best_guess) else {
                 return (Failure, grid.into_iter().take(guess_num).collect());
             };
-            Self::enter_guess(best_guess, grid.guess_mut(guess_num), &answer_upper);
-            if best_guess == answer_upper {
+            let solved = row.pattern().is_solved();
+            *grid.guess_mut(guess_num) = row;
+            if solved {
                 return (Success, grid.into_iter().take(guess_num + 1).collect());
             }
         }
         (Failure, grid.into_iter().collect())
     }
+}
 
-    fn enter_guess(guess: &str, output: &mut GuessStr<N>, answer: &str) {
-        let mut chars_count = util::chars_count(answer.chars());
-        let mut answer_chars = ['\x00'; N];
-        for (idx, mut answer_ch) in answer.chars().enumerate() {
-            answer_ch = answer_ch.to_ascii_uppercase();
-            answer_chars[idx] = answer_ch;
-        }
-        // Do this in two passes: first to find all the chars that are in the right position,
-        // and then the rest.
-        for (idx, mut guess_str_char) in guess.chars().enumerate() {
-            guess_str_char = guess_str_char.to_ascii_uppercase();
-            let guess_ch = output.guess_mut(idx);
-            guess_ch.set_ch(guess_str_char);
-            if guess_str_char == answer_chars[idx] {
-                guess_ch.set_knowledge(CharKnowledge::Correct);
-                chars_count.decrement(guess_str_char);
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::analyzer::standard_suite;
+    use crate::analyze::simple_analyzers::{CharFrequencies, WordFrequencies};
+
+    /// However an analyzer plays, the feedback on each of its guesses is what Wordle would have
+    /// given, and it never rules out the answer.
+    #[test]
+    fn guesses_never_contradict_the_answer() {
+        let words: WordList<5> = WordList::std_with_limit(500).unwrap();
+        let analyzers: Vec<Box<dyn Analyzer<5>>> =
+            vec![Box::new(WordFrequencies {}), Box::new(CharFrequencies {})];
+        for answer in words.words().step_by(10).map(|wf| wf.word.as_str()) {
+            for analyzer in &analyzers {
+                for hard_mode in [false, true] {
+                    let (_, rows) = AutoGuesser::<5>::guess_one(
+                        &words,
+                        &words,
+                        answer,
+                        analyzer.as_ref(),
+                        6,
+                        hard_mode,
+                    );
+                    let mut grid: GuessGrid<5> = GuessGrid::new(6);
+                    for (idx, row) in rows.iter().enumerate() {
+                        let guess = row.word();
+                        grid.guess_mut(idx).set_scored(&guess, answer);
+                        assert_eq!(grid.guesses()[idx].feedback(), row.feedback());
+                        assert!(
+                            KnownWordConstraints::from_grid(&grid).is_word_possible(answer),
+                            "{} ruled out {} after guessing {}",
+                            analyzer.name(),
+                            answer,
+                            guess
+                        );
+                    }
+                }
             }
         }
-        // Now, chars that might be in the wrong position
-        for (idx, mut guess_str_char) in guess.chars().enumerate() {
-            guess_str_char = guess_str_char.to_ascii_uppercase();
-            let guess_ch = output.guess_mut(idx);
-            if guess_ch.knowledge() == CharKnowledge::Correct {
-                continue; // already handled above
-            }
-            if let Some(count) = chars_count.get_mut(guess_str_char) {
-                if *count <= 0 {
-                    guess_ch.set_knowledge(CharKnowledge::Missing);
-                } else {
-                    guess_ch.set_knowledge(CharKnowledge::WrongPosition);
-                    *count -= 1;
+    }
+
+    /// The game is lost once every row has been used, however many rows there are.
+    #[test]
+    fn failure_after_all_rows() {
+        let words: WordList<5> = WordList::std_with_limit(500).unwrap();
+        // The most common word always comes first, so any other answer needs at least two guesses.
+        let answer = words.words().nth(100).unwrap().word.clone();
+        let (result, rows) =
+            AutoGuesser::<5>::guess_one(&words, &words, &answer, &WordFrequencies {}, 1, false);
+        assert!(matches!(result, Failure));
+        assert_eq!(1, rows.len());
+
+        let (result, rows) =
+            AutoGuesser::<5>::guess_one(&words, &words, &answer, &WordFrequencies {}, 10, false);
+        assert!(matches!(result, Success));
+        assert!(rows.len() > 1);
+    }
+
+    /// Every analyzer can play words of any length, not just five letters.
+    #[test]
+    fn other_lengths() {
+        fn solves_all<const N: usize>(words: &[&str]) {
+            let words: WordList<N> = WordList::from_words(words);
+            for analyzer in standard_suite::<N>() {
+                for answer in words.words().map(|wf| wf.word.as_str()) {
+                    let (result, rows) = AutoGuesser::<N>::guess_one(
+                        &words,
+                        &words,
+                        answer,
+                        analyzer.as_ref(),
+                        13,
+                        false,
+                    );
+                    assert!(
+                        matches!(result, Success),
+                        "{} lost on {}",
+                        analyzer.name(),
+                        answer
+                    );
+                    assert_eq!(answer, rows.last().unwrap().word());
                 }
             }
         }
+        solves_all::<4>(&["SALT", "SLAT", "LAST", "MOLE", "CORE", "CARE"]);
+        solves_all::<6>(&["PLANET", "PLANER", "STAIRS", "STAINS", "ROVERS", "COVERS"]);
+        solves_all::<7>(&["PLANETS", "PLANERS", "READING", "HEADING", "BOXLIKE"]);
+    }
+
+    /// However many threads the games run on, the results come out in the same order.
+    #[test]
+    fn same_order_for_any_thread_count() {
+        let words: WordList<5> = WordList::std_with_limit(300).unwrap();
+        let answers: Vec<String> = words.words().take(20).map(|wf| wf.word.clone()).collect();
+        let run = |threads| {
+            let auto_guesser: AutoGuesser<5> = AutoGuesser {
+                answer_words: answers.clone(),
+                guesses_list: words.clone(),
+                answers_list: words.clone(),
+                analyzers: vec![Box::new(WordFrequencies {}), Box::new(CharFrequencies {})],
+                rows: 6,
+                hard_mode: false,
+                threads,
+            };
+            let results: Vec<(String, String, Vec<String>)> = auto_guesser
+                .guess_all()
+                .unwrap()
+                .into_iter()
+                .flat_map(|by_word| {
+                    let answer = by_word.answer;
+                    by_word.analyzer_results.into_iter().map(move |result| {
+                        let guesses = result.guesses.iter().map(|g| g.word()).collect();
+                        (answer.clone(), result.name, guesses)
+                    })
+                })
+                .collect();
+            results
+        };
+        let single_threaded = run(1);
+        assert_eq!(40, single_threaded.len());
+        assert_eq!(answers[0], single_threaded[0].0);
+        assert_eq!("Most Common Words", single_threaded[0].1);
+        assert_eq!(single_threaded, run(4));
+    }
+
+    #[test]
+    fn wrong_length_answer() {
+        let words: WordList<5> = WordList::from_words(&["CRANE", "STAIR"]);
+        let auto_guesser: AutoGuesser<5> = AutoGuesser {
+            answer_words: vec!["CRANE".to_string(), "STAIRS".to_string()],
+            guesses_list: words.clone(),
+            answers_list: words,
+            analyzers: vec![Box::new(WordFrequencies {})],
+            rows: 6,
+            hard_mode: false,
+            threads: 1,
+        };
+        let Err(err) = auto_guesser.guess_all() else {
+            panic!("STAIRS has 6 letters");
+        };
+        assert_eq!("expected 5-letter answers, but got \"STAIRS\"", err);
+    }
+
+    /// Against Absurdle, every analyzer eventually wins, given enough rows; and the answer it
+    /// ends up with is the last guess.
+    #[test]
+    fn adversarial() {
+        let words: WordList<5> = WordList::std_with_limit(300).unwrap();
+        let auto_guesser: AutoGuesser<5> = AutoGuesser {
+            answer_words: Vec::new(),
+            guesses_list: words.clone(),
+            answers_list: words,
+            analyzers: vec![Box::new(WordFrequencies {}), Box::new(CharFrequencies {})],
+            rows: 13,
+            hard_mode: false,
+            threads: 1,
+        };
+        let results = auto_guesser.guess_adversarial().unwrap();
+        assert_eq!(2, results.len());
+        for by_word in results {
+            let [result] = &by_word.analyzer_results[..] else {
+                panic!("expected one game per analyzer");
+            };
+            assert!(matches!(result.result, Success), "{} lost", result.name);
+            assert_eq!(by_word.answer, result.guesses.last().unwrap().word());
+        }
     }
 }
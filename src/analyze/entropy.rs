@@ -0,0 +1,136 @@
+use crate::analyze::analyzer::{Analyzer, ScoredWord};
+use crate::analyze::pattern::{
+    guesses_to_score, AnswerPatterns, ArrayBasedPatternBuckets, HashMapBasedPatternBuckets,
+    PatternBuckets, MAX_WORD_LEN_FOR_ARRAY_BUCKETS,
+};
+use crate::word_list::{WordFreq, WordList};
+
+/// Scores each guess by the Shannon entropy of the patterns it would produce; that is, how many
+/// bits of information we expect to get from it.
+///
+/// `PatternBasedAnalyzer` only counts how many distinct patterns a guess can produce. That treats a
+/// guess that splits 100 words into 99 + 1 the same as one that splits them 50 + 50, even though the
+/// second one is far more useful. Entropy accounts for how evenly the words are spread out.
+pub struct EntropyAnalyzer<const N: usize> {
+    /// If true, each possible answer counts towards its pattern's bucket in proportion to its
+    /// `WordFreq::freq`, rather than each answer counting equally. This models the idea that
+    /// common words are more likely to be the answer.
+    pub weight_by_freq: bool,
+}
+
+impl<const N: usize> Analyzer<N> for EntropyAnalyzer<N> {
+    fn name(&self) -> String {
+        if self.weight_by_freq {
+            "Entropy (freq-weighted)".to_string()
+        } else {
+            "Entropy".to_string()
+        }
+    }
+
+    fn analyze<'a>(
+        &self,
+        guesses: &'a WordList<N>,
+        answers: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
+        let answer_patterns = AnswerPatterns::new(answers);
+        let total_weight: f64 = answers.words().map(|w| self.weight(w)).sum();
+
+        // A guess that might be the answer also has a chance of ending the game outright. Give it a
+        // small bonus for that, equal to the probability that it's the answer. This mostly serves
+        // to break ties in favor of possible answers.
+        guesses_to_score(guesses, answers)
+            .into_iter()
+            .map(|(w, is_answer)| {
+                let mut score = self.score_word(&w.word, &answer_patterns);
+                if is_answer && total_weight > 0.0 {
+                    score += self.weight(w) / total_weight;
+                }
+                ScoredWord {
+                    word: &w.word,
+                    score,
+                }
+            })
+            .collect()
+    }
+
+    fn uses_pattern_matrix(&self) -> bool {
+        true
+    }
+}
+
+impl<const N: usize> EntropyAnalyzer<N> {
+    fn weight(&self, word: &WordFreq) -> f64 {
+        if self.weight_by_freq {
+            word.freq
+        } else {
+            1.0
+        }
+    }
+
+    fn score_word(&self, word: &str, answer_patterns: &AnswerPatterns<N>) -> f64 {
+        if N <= MAX_WORD_LEN_FOR_ARRAY_BUCKETS {
+            self.score_word_0::<ArrayBasedPatternBuckets<N>>(word, answer_patterns)
+        } else {
+            self.score_word_0::<HashMapBasedPatternBuckets<N>>(word, answer_patterns)
+        }
+    }
+
+    fn score_word_0<B: PatternBuckets<N>>(
+        &self,
+        word: &str,
+        answer_patterns: &AnswerPatterns<N>,
+    ) -> f64 {
+        let mut buckets = B::new();
+        let mut total_weight = 0.0;
+        answer_patterns.for_each(word, |pattern, answer| {
+            let weight = self.weight(answer);
+            buckets.add(pattern, weight);
+            total_weight += weight;
+        });
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+        let mut entropy = 0.0;
+        buckets.for_each_bucket(|weight| {
+            if weight > 0.0 {
+                let p = weight / total_weight;
+                entropy -= p * p.log2();
+            }
+        });
+        entropy
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn even_split_beats_lopsided_split() {
+        // "ABC" splits these into four buckets, with at most two words in any of them. "AXX" splits
+        // them into three buckets, and the words are less evenly spread among those.
+        let words = WordList::from_words(&["AYY", "BYY", "CYY", "ABC", "AXX"]);
+        let analyzer = EntropyAnalyzer::<3> {
+            weight_by_freq: false,
+        };
+        let scores = analyzer.analyze(&words, &words);
+        let score_of = |word: &str| scores.iter().find(|s| s.word == word).unwrap().score;
+
+        assert!(score_of("ABC") > score_of("AXX"));
+    }
+
+    #[test]
+    fn entropy_in_bits() {
+        // Against these four answers, "AB" produces four distinct, equally likely patterns:
+        // 2 bits. "CD" produces the same (all-missing) pattern for every answer: 0 bits.
+        let words = WordList::from_words(&["AB", "BA", "AA", "BB"]);
+        let analyzer = EntropyAnalyzer::<2> {
+            weight_by_freq: false,
+        };
+
+        let answers = AnswerPatterns::new(&words);
+
+        assert_eq!(2.0, analyzer.score_word("AB", &answers));
+        assert_eq!(0.0, analyzer.score_word("CD", &answers));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::analyze::analyzer::{Analyzer, ScoredWord};
+use crate::analyze::pattern::{
+    guesses_to_score, AnswerPatterns, ArrayBasedPatternBuckets, HashMapBasedPatternBuckets,
+    PatternBuckets, MAX_WORD_LEN_FOR_ARRAY_BUCKETS,
+};
+use crate::word_list::WordList;
+
+/// Knuth's minimax approach: scores each guess by its worst case. For every pattern a guess can
+/// produce, we look at how many words would still be possible; the guess's worst case is the
+/// largest of those buckets.
+///
+/// The score is the number of words that the guess is guaranteed to eliminate (that is, the total
+/// number of words minus the largest bucket), so that higher is still better. As in Knuth's
+/// original, ties go to guesses that might be the answer.
+pub struct MinimaxAnalyzer<const N: usize> {}
+
+impl<const N: usize> Analyzer<N> for MinimaxAnalyzer<N> {
+    fn name(&self) -> String {
+        "Minimax".to_string()
+    }
+
+    fn analyze<'a>(
+        &self,
+        guesses: &'a WordList<N>,
+        answers: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
+        let answer_patterns = AnswerPatterns::new(answers);
+        let total = answers.len();
+
+        guesses_to_score(guesses, answers)
+            .into_iter()
+            .map(|(w, is_answer)| {
+                let eliminated = total - Self::largest_bucket(&w.word, &answer_patterns);
+                // The eliminated count is a whole number, so this only ever breaks ties.
+                let tie_breaker = if is_answer { 0.5 } else { 0.0 };
+                ScoredWord {
+                    word: &w.word,
+                    score: eliminated as f64 + tie_breaker,
+                }
+            })
+            .collect()
+    }
+
+    fn uses_pattern_matrix(&self) -> bool {
+        true
+    }
+}
+
+impl<const N: usize> MinimaxAnalyzer<N> {
+    fn largest_bucket(word: &str, answer_patterns: &AnswerPatterns<N>) -> usize {
+        if N <= MAX_WORD_LEN_FOR_ARRAY_BUCKETS {
+            Self::largest_bucket_0::<ArrayBasedPatternBuckets<N>>(word, answer_patterns)
+        } else {
+            Self::largest_bucket_0::<HashMapBasedPatternBuckets<N>>(word, answer_patterns)
+        }
+    }
+
+    fn largest_bucket_0<B: PatternBuckets<N>>(
+        word: &str,
+        answer_patterns: &AnswerPatterns<N>,
+    ) -> usize {
+        let mut buckets = B::new();
+        answer_patterns.for_each(word, |pattern, _| buckets.add(pattern, 1.0));
+        let mut largest = 0.0_f64;
+        buckets.for_each_bucket(|count| largest = largest.max(count));
+        largest as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn even_split_beats_big_worst_case() {
+        // Against these answers, "ABC" gives each one a different pattern, so it's guaranteed to
+        // leave just one. "AXX" only tells AYY apart from the rest, so its worst case leaves three.
+        let answers = WordList::from_words(&["AYY", "BYY", "CYY", "DYY"]);
+        let guesses = WordList::from_words(&["AYY", "BYY", "CYY", "DYY", "ABC", "AXX"]);
+        let scores = MinimaxAnalyzer::<3> {}.analyze(&guesses, &answers);
+        let score_of = |word: &str| scores.iter().find(|s| s.word == word).unwrap().score;
+
+        assert_eq!(3.0, score_of("ABC"));
+        assert_eq!(1.0, score_of("AXX"));
+    }
+
+    #[test]
+    fn ties_go_to_possible_answers() {
+        // Each of these guesses tells the two answers apart, but only AYY and BYY can win outright.
+        let answers = WordList::from_words(&["AYY", "BYY"]);
+        let guesses = WordList::from_words(&["AZZ", "AYY", "BYY"]);
+        let mut scores = MinimaxAnalyzer::<3> {}.analyze(&guesses, &answers);
+        scores.sort();
+        let words: Vec<&str> = scores.iter().map(|s| s.word).collect();
+
+        assert_eq!("AZZ", words[2]);
+        assert_eq!(scores[0].score, scores[1].score);
+        assert_eq!(1.0, scores[2].score);
+    }
+}
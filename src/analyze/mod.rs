@@ -1,8 +1,14 @@
 pub mod analyzer;
 pub mod auto_guesser;
 mod char_stats;
+mod entropy;
+mod minimax;
+pub mod multi_board;
 mod pattern;
+pub mod pattern_matrix;
 mod position_chars;
+pub mod results_output;
 mod scored_chars;
 mod simple_analyzers;
-mod util;
+pub mod strategy_stats;
+pub(crate) mod util;
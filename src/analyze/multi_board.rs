@@ -0,0 +1,69 @@
+use crate::analyze::analyzer::{Analyzer, ScoredWord};
+use crate::word_list::WordList;
+use std::collections::HashMap;
+
+/// Scores guesses for a multi-board game (see `MultiBoard`), by running `analyzer` on each board
+/// that still has possible answers, and combining the results.
+///
+/// Each board's scores are normalized to 0-100 (see `ScoredWord::normalize_scores`), so that every
+/// board counts equally, and a guess's score is the sum of its scores across the boards. On top of
+/// that, a guess gets up to 100 more for each board it might solve outright: 100 divided by the
+/// number of words that board could still be. Without that, a board that's down to its last word
+/// or two would count for nothing, since no guess could narrow it down any further.
+///
+/// The results aren't sorted or normalized, just like `Analyzer::analyze`'s.
+///
+/// [`MultiBoard`]: crate::guess::multi_board::MultiBoard
+pub fn analyze_boards<'a, const N: usize>(
+    analyzer: &dyn Analyzer<N>,
+    guesses: &'a WordList<N>,
+    boards: &'a [WordList<N>],
+) -> Vec<ScoredWord<'a>> {
+    let mut combined: HashMap<&'a str, f64> = HashMap::new();
+    for answers in boards.iter().filter(|answers| !answers.is_empty()) {
+        let mut scored = analyzer.analyze(guesses, answers);
+        ScoredWord::normalize_scores(&mut scored);
+        for ScoredWord { word, score } in scored {
+            *combined.entry(word).or_default() += score;
+        }
+        let solve_chance = 100.0 / answers.len() as f64;
+        for answer in answers.words() {
+            *combined.entry(&answer.word).or_default() += solve_chance;
+        }
+    }
+    combined
+        .into_iter()
+        .map(|(word, score)| ScoredWord { word, score })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::simple_analyzers::WordFrequencies;
+
+    #[test]
+    fn solving_a_board_outright_wins() {
+        // WordFrequencies scores each board's words by their frequency. On its own, board 1 would
+        // pick CRANE; but board 2 has only one word left, and guessing it solves that board for
+        // sure.
+        let guesses: WordList<5> = WordList::from_words(&["CRANE", "SLATE", "TRAIN"]);
+        let boards = vec![
+            WordList::from_words(&["CRANE", "SLATE"]),
+            WordList::from_words(&["SLATE"]),
+        ];
+        let mut scored = analyze_boards(&WordFrequencies {}, &guesses, &boards);
+        scored.sort();
+        let words: Vec<&str> = scored.iter().map(|sw| sw.word).collect();
+        assert_eq!(vec!["SLATE", "CRANE"], words);
+    }
+
+    #[test]
+    fn empty_boards_are_ignored() {
+        let guesses: WordList<5> = WordList::from_words(&["CRANE", "SLATE"]);
+        let boards = vec![WordList::empty(), WordList::from_words(&["SLATE"])];
+        let scored = analyze_boards(&WordFrequencies {}, &guesses, &boards);
+        assert_eq!(1, scored.len());
+        assert_eq!("SLATE", scored[0].word);
+    }
+}
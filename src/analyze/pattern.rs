@@ -1,37 +1,46 @@
 use crate::analyze::analyzer::{Analyzer, ScoredWord};
+use crate::analyze::pattern_matrix::PatternMatrix;
 use crate::analyze::util::CharsCount;
-use crate::guess::known_word_constraints::CharKnowledge;
+use crate::guess::pattern::Pattern;
 use crate::word_list::{WordFreq, WordList};
 use bitvec::vec::BitVec;
-use std::collections::HashSet;
-use std::hash::{Hash, Hasher};
-use strum::EnumCount;
+use std::collections::{HashMap, HashSet};
 
-const MAX_WORD_LEN_FOR_BITVEC: usize = 5;
+/// The longest words to use `BitBasedPatternSet` for. Its bits grow as `3^N`, but even at 8
+/// letters that's under 1KB, which is still cheaper to fill than a `HashSet`.
+pub(super) const MAX_WORD_LEN_FOR_BITVEC: usize = 8;
 
-pub struct PatternBasedAnalyzer<const N: usize> {
-    pub limit: usize,
-}
+/// The longest words to use `ArrayBasedPatternBuckets` for. Each bucket is an `f64`, so at 7
+/// letters the array would be 17KB per guess: more than it's worth, when most of the buckets will
+/// be empty.
+pub(super) const MAX_WORD_LEN_FOR_ARRAY_BUCKETS: usize = 6;
+
+pub struct PatternBasedAnalyzer<const N: usize> {}
 
 /// An implementation of roughly what I think the WorldBot uses
 impl<const N: usize> Analyzer<N> for PatternBasedAnalyzer<N> {
     fn name(&self) -> String {
-        format!("Pattern ({})", self.limit)
+        "Pattern".to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
-        let words_list_copy = words_list.reify();
+    fn analyze<'a>(
+        &self,
+        guesses: &'a WordList<N>,
+        answers: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
+        let answer_patterns = AnswerPatterns::new(answers);
 
-        let mut words_and_scores: Vec<(ScoredWord<'a>, f64)> = words_list
-            .words()
-            .take(self.limit)
-            .map(|w| {
+        // Only possible answers get the frequency bonus: a word that's been ruled out can't win,
+        // no matter how common it is.
+        let mut words_and_scores: Vec<(ScoredWord<'a>, f64)> = guesses_to_score(guesses, answers)
+            .into_iter()
+            .map(|(w, is_answer)| {
                 (
                     ScoredWord {
                         word: &w.word,
-                        score: Self::score_word(&w.word, &words_list_copy) as f64,
+                        score: Self::score_word(&w.word, &answer_patterns) as f64,
                     },
-                    w.freq,
+                    if is_answer { w.freq } else { 0.0 },
                 )
             })
             .collect();
@@ -49,7 +58,7 @@ impl<const N: usize> Analyzer<N> for PatternBasedAnalyzer<N> {
         if max_freq_and_score.0 > 0 {
             let max_freq = max_freq_and_score.1;
             let max_score = max_freq_and_score.2;
-            for mut entry in words_and_scores.iter_mut() {
+            for entry in words_and_scores.iter_mut() {
                 let normalized_freq = entry.1 / max_freq;
                 let normalized_score = entry.0.score / max_score;
                 entry.0.score = normalized_score * 10.0 + normalized_freq;
@@ -61,87 +70,118 @@ impl<const N: usize> Analyzer<N> for PatternBasedAnalyzer<N> {
             .map(|(scored_word, _)| scored_word)
             .collect()
     }
+
+    fn uses_pattern_matrix(&self) -> bool {
+        true
+    }
 }
 
 impl<const N: usize> PatternBasedAnalyzer<N> {
-    fn score_word(word: &str, all_words: &WordList<N>) -> usize {
+    fn score_word(word: &str, answer_patterns: &AnswerPatterns<N>) -> usize {
         if N <= MAX_WORD_LEN_FOR_BITVEC {
-            Self::score_word_0::<BitBasedPatternSet<N>>(word, &all_words)
+            Self::score_word_0::<BitBasedPatternSet<N>>(word, answer_patterns)
         } else {
-            Self::score_word_0::<HashSetBasedPatternSet<N>>(word, &all_words)
+            Self::score_word_0::<HashSetBasedPatternSet<N>>(word, answer_patterns)
         }
     }
 
-    fn score_word_0<P: PatternSet<N>>(word: &str, all_words: &WordList<N>) -> usize {
-        // These three are basically "scratch space" that all of the words will use for this guess.
-        // Benchmarking and experimentation suggests that this is the best place to create them:
-        // if we create them within the loop, it's too costly, but if we create them outside the
-        // overall score-all-words loop (in analyze(~), above), it gets slower again for some
-        // reason I don't fully understand.
+    fn score_word_0<P: PatternSet<N>>(word: &str, answer_patterns: &AnswerPatterns<N>) -> usize {
         let mut patterns = P::new();
-        let mut answer_arr = ['\x00'; N];
-        let mut answer_chars_count = CharsCount::default();
-
-        for WordFreq {
-            word: if_answer, ..
-        } in all_words.words()
-        {
-            answer_chars_count.reset_all();
-            for (idx, ch) in if_answer.chars().enumerate() {
-                answer_arr[idx] = ch;
-                answer_chars_count.increment(ch);
-            }
-            patterns.add(&Self::pattern(word, &answer_arr, &mut answer_chars_count));
-        }
+        answer_patterns.for_each(word, |pattern, _| patterns.add(pattern));
         patterns.size()
     }
+}
 
-    fn pattern(guess: &str, answer: &[char; N], answer_chars_count: &mut CharsCount) -> Pattern<N> {
-        let mut result = Pattern {
-            knowledge: [CharKnowledge::Missing; N],
-        };
-        // first, all the ones in the right position
-        for (idx, guess_ch) in guess.chars().enumerate() {
-            if guess_ch == answer[idx] {
-                result.knowledge[idx] = CharKnowledge::Correct;
-                answer_chars_count.decrement(guess_ch);
-            }
+/// Picks the words that a pattern-based analyzer should score, and pairs each with whether it's one
+/// of the possible `answers`.
+///
+/// All of the possible answers come first, so that the answer itself is always in the running.
+/// After that come the rest of the allowed `guesses`: these have already been ruled out as answers,
+/// but they can still be good "probe" words that split the remaining answers well.
+pub(super) fn guesses_to_score<'a, const N: usize>(
+    guesses: &'a WordList<N>,
+    answers: &'a WordList<N>,
+) -> Vec<(&'a WordFreq, bool)> {
+    let answer_words: HashSet<&str> = answers.words().map(|w| w.word.as_str()).collect();
+    let probes = guesses
+        .words()
+        .filter(|w| !answer_words.contains(w.word.as_str()));
+    answers
+        .words()
+        .map(|w| (w, true))
+        .chain(probes.map(|w| (w, false)))
+        .collect()
+}
+
+/// The possible answers, ready to have guesses' patterns computed against them. If the answers'
+/// word list has a `PatternMatrix`, the patterns come from there; otherwise, they're computed as
+/// needed.
+pub(super) struct AnswerPatterns<'a, const N: usize> {
+    answers: Vec<&'a WordFreq>,
+    /// The matrix, and each answer's index in it.
+    matrix: Option<(&'a PatternMatrix<N>, Vec<usize>)>,
+}
+
+impl<'a, const N: usize> AnswerPatterns<'a, N> {
+    pub(super) fn new(answers: &'a WordList<N>) -> Self {
+        let answer_words: Vec<&WordFreq> = answers.words().collect();
+        let matrix = answers.pattern_matrix().and_then(|matrix| {
+            let indexes: Option<Vec<usize>> = answer_words
+                .iter()
+                .map(|wf| matrix.index_of(&wf.word))
+                .collect();
+            indexes.map(|indexes| (matrix.as_ref(), indexes))
+        });
+        AnswerPatterns {
+            answers: answer_words,
+            matrix,
         }
+    }
 
-        // now all the ones in the wrong position
-        for (idx, guess_ch) in guess.chars().enumerate() {
-            if let Some(remaining) = answer_chars_count.get_mut(guess_ch) {
-                if *remaining > 0 {
-                    result.knowledge[idx] = CharKnowledge::WrongPosition;
-                    *remaining -= 1;
-                }
+    /// Passes the pattern code (see `Pattern::code`) that `guess` would get for each of
+    /// the answers, along with the answer it came from, to `action`.
+    pub(super) fn for_each<F>(&self, guess: &str, mut action: F)
+    where
+        F: FnMut(usize, &WordFreq),
+    {
+        if let Some((matrix, indexes)) = &self.matrix {
+            if let Some(row) = matrix.row(guess) {
+                row.for_each(indexes, |i, code| action(code, self.answers[i]));
+                return;
             }
         }
-        result
-    }
-}
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-struct Pattern<const N: usize> {
-    knowledge: [CharKnowledge; N],
-}
+        // These two are basically "scratch space" that all of the words will use for this guess.
+        // Benchmarking and experimentation suggests that this is the best place to create them:
+        // if we create them within the loop, it's too costly, but if we create them outside the
+        // overall score-all-words loop (in each analyzer's analyze(~)), it gets slower again for
+        // some reason I don't fully understand.
+        let mut answer_arr = ['\x00'; N];
+        let mut answer_chars_count = CharsCount::default();
 
-impl<const N: usize> Hash for Pattern<N> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        for k in self.knowledge {
-            state.write_usize(k as usize);
+        for if_answer in &self.answers {
+            answer_chars_count.reset_all();
+            for (idx, ch) in if_answer.word.chars().enumerate() {
+                answer_arr[idx] = ch;
+                answer_chars_count.increment(ch);
+            }
+            action(
+                Pattern::score_counted(guess, &answer_arr, &mut answer_chars_count).code(),
+                if_answer,
+            );
         }
     }
 }
 
+/// A set of patterns, by their codes (see `Pattern::code`).
 trait PatternSet<const N: usize> {
     fn new() -> Self;
-    fn add(&mut self, pattern: &Pattern<N>);
+    fn add(&mut self, pattern: usize);
     fn size(&self) -> usize;
 }
 
 struct HashSetBasedPatternSet<const N: usize> {
-    patterns: HashSet<Pattern<N>>,
+    patterns: HashSet<usize>,
 }
 
 impl<const N: usize> PatternSet<N> for HashSetBasedPatternSet<N> {
@@ -151,8 +191,8 @@ impl<const N: usize> PatternSet<N> for HashSetBasedPatternSet<N> {
         }
     }
 
-    fn add(&mut self, pattern: &Pattern<N>) {
-        self.patterns.insert(pattern.clone());
+    fn add(&mut self, pattern: usize) {
+        self.patterns.insert(pattern);
     }
 
     fn size(&self) -> usize {
@@ -160,12 +200,8 @@ impl<const N: usize> PatternSet<N> for HashSetBasedPatternSet<N> {
     }
 }
 
-/// A `PatternSet` that treats each `Pattern` as a number, and uses a `BitArray` to keep track of
-/// which "numbers" are in the set.
-///
-/// A `Pattern` is just an array of `CharKnowledge`, which is an enum. If we consider each enum
-/// value a "digit" (and there are 4 of them), this means `Pattern` is basically an `N`-digit number
-/// in base 4. For `N=5`, this is 1024 values.
+/// A `PatternSet` that uses a `BitVec` to keep track of which pattern codes are in the set. The
+/// codes are `N`-digit numbers in base 3, so for `N=5`, this is 243 values.
 struct BitBasedPatternSet<const N: usize> {
     patterns: BitVec,
     count: usize,
@@ -174,14 +210,13 @@ struct BitBasedPatternSet<const N: usize> {
 impl<const N: usize> PatternSet<N> for BitBasedPatternSet<N> {
     fn new() -> Self {
         Self {
-            patterns: BitVec::repeat(false, CharKnowledge::COUNT.pow(N as u32)),
+            patterns: BitVec::repeat(false, Pattern::<N>::COUNT),
             count: 0,
         }
     }
 
-    fn add(&mut self, pattern: &Pattern<N>) {
-        let pattern_as_usize = Self::pattern_to_usize(&pattern);
-        if !self.patterns.replace(pattern_as_usize, true) {
+    fn add(&mut self, pattern: usize) {
+        if !self.patterns.replace(pattern, true) {
             self.count += 1;
         }
     }
@@ -191,17 +226,97 @@ impl<const N: usize> PatternSet<N> for BitBasedPatternSet<N> {
     }
 }
 
-impl<const N: usize> BitBasedPatternSet<N> {
-    #[inline]
-    fn pattern_to_usize(pattern: &Pattern<N>) -> usize {
-        let mut result = 0;
-        let mut position_factor = 1;
-        for position in 0..N {
-            let digit = pattern.knowledge[position] as usize;
-            result += digit * position_factor;
-            position_factor *= CharKnowledge::COUNT;
+/// A tally of how much weight falls into each pattern's "bucket". Unlike a `PatternSet`, which
+/// only cares about which patterns we've seen, this lets analyzers reason about how evenly the
+/// answers are spread among those patterns.
+pub(super) trait PatternBuckets<const N: usize> {
+    fn new() -> Self;
+    fn add(&mut self, pattern: usize, weight: f64);
+    fn for_each_bucket<F: FnMut(f64)>(&self, action: F);
+}
+
+pub(super) struct HashMapBasedPatternBuckets<const N: usize> {
+    buckets: HashMap<usize, f64>,
+}
+
+impl<const N: usize> PatternBuckets<N> for HashMapBasedPatternBuckets<N> {
+    fn new() -> Self {
+        Self {
+            buckets: Default::default(),
         }
+    }
+
+    fn add(&mut self, pattern: usize, weight: f64) {
+        *self.buckets.entry(pattern).or_default() += weight;
+    }
+
+    fn for_each_bucket<F: FnMut(f64)>(&self, action: F) {
+        self.buckets.values().copied().for_each(action);
+    }
+}
+
+/// The `PatternBuckets` analog of `BitBasedPatternSet`: each pattern code indexes into a flat array
+/// of weights.
+pub(super) struct ArrayBasedPatternBuckets<const N: usize> {
+    buckets: Vec<f64>,
+}
 
-        return result;
+impl<const N: usize> PatternBuckets<N> for ArrayBasedPatternBuckets<N> {
+    fn new() -> Self {
+        Self {
+            buckets: vec![0.0; Pattern::<N>::COUNT],
+        }
+    }
+
+    fn add(&mut self, pattern: usize, weight: f64) {
+        self.buckets[pattern] += weight;
+    }
+
+    fn for_each_bucket<F: FnMut(f64)>(&self, action: F) {
+        self.buckets
+            .iter()
+            .copied()
+            .filter(|w| *w > 0.0)
+            .for_each(action);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::entropy::EntropyAnalyzer;
+    use crate::analyze::minimax::MinimaxAnalyzer;
+
+    #[test]
+    fn possible_answers_come_first() {
+        let answers = WordList::<3>::from_words(&["BYY", "AYY"]);
+        let guesses = WordList::from_words(&["ABC", "AYY", "BYY", "AXX"]);
+        let scored: Vec<(&str, bool)> = guesses_to_score(&guesses, &answers)
+            .into_iter()
+            .map(|(w, is_answer)| (w.word.as_str(), is_answer))
+            .collect();
+        assert_eq!(
+            vec![("BYY", true), ("AYY", true), ("ABC", false), ("AXX", false)],
+            scored
+        );
+    }
+
+    #[test]
+    fn ruled_out_probe_can_be_the_best_guess() {
+        // ABC can't be the answer, but it gives each of the answers a different pattern. Any of
+        // the answers would only tell its own word apart from the other three.
+        let answers = WordList::<3>::from_words(&["AYY", "BYY", "CYY", "DYY"]);
+        let guesses = WordList::from_words(&["AYY", "BYY", "CYY", "DYY", "ABC"]);
+        let analyzers: Vec<Box<dyn Analyzer<3>>> = vec![
+            Box::new(EntropyAnalyzer {
+                weight_by_freq: false,
+            }),
+            Box::new(MinimaxAnalyzer {}),
+        ];
+        for analyzer in analyzers {
+            let mut scores = analyzer.analyze(&guesses, &answers);
+            scores.sort();
+            assert_eq!("ABC", scores[0].word, "{}", analyzer.name());
+        }
     }
 }
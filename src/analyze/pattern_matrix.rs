@@ -0,0 +1,336 @@
+use crate::analyze::util::{chars_count, CharsCount};
+use crate::guess::pattern::Pattern;
+use crate::word_list::WordList;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Bump this whenever the way patterns are computed or stored changes, so that stale cache files
+/// are ignored.
+const CACHE_FORMAT_VERSION: u32 = 2;
+const CACHE_MAGIC: &[u8; 4] = b"WHPM";
+
+/// The pattern that every word in a list would get against every other word in it, as guess and
+/// answer respectively. Pattern-based analyzers spend almost all of their time computing these, and
+/// the auto-guesser asks for the same ones game after game; so we compute them all once, up front.
+///
+/// Each pattern is stored as its code (see `Pattern::code`). There are `3^N`
+/// possible codes, so for `N <= 5` each one fits in a `u8`; up to `N = 10`, a `u16`.
+pub struct PatternMatrix<const N: usize> {
+    /// Each word's index, for both rows (as a guess) and columns (as an answer). If a word appears
+    /// in the list more than once, this is its last index; that's fine, since all of its rows and
+    /// columns are the same.
+    index: HashMap<String, usize>,
+    len: usize,
+    cells: Cells,
+    hash: u64,
+}
+
+enum Cells {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+}
+
+/// One guess's patterns against every answer, indexed by the answer's position in the matrix.
+pub(super) enum PatternRow<'a> {
+    U8(&'a [u8]),
+    U16(&'a [u16]),
+}
+
+impl<const N: usize> PatternMatrix<N> {
+    /// The longest words we can build a matrix for: any longer, and the codes don't fit in a `u16`.
+    pub const MAX_WORD_LEN: usize = 10;
+
+    /// The most memory a matrix may take. Any bigger, and it's not worth it: the analyzers compute
+    /// the patterns they need as they go instead, which is slower, but doesn't need the memory.
+    pub const MAX_BYTES: usize = 256 * 1024 * 1024;
+
+    /// Whether a matrix of `word_count` words fits in `MAX_BYTES`.
+    pub fn fits(word_count: usize) -> bool {
+        let cell_bytes = if N <= 5 { 1 } else { 2 };
+        word_count
+            .checked_mul(word_count)
+            .and_then(|cells| cells.checked_mul(cell_bytes))
+            .is_some_and(|bytes| bytes <= Self::MAX_BYTES)
+    }
+
+    /// Computes the patterns for every pair of words in `words`. This takes a while for large lists
+    /// (it's quadratic), so it runs in parallel.
+    pub fn build(words: &WordList<N>) -> Result<Self, String> {
+        let words: Vec<&str> = words.words().map(|wf| wf.word.as_str()).collect();
+        let len = words.len();
+        let answers: Vec<([char; N], CharsCount)> = words
+            .iter()
+            .map(|word| {
+                let mut chars = ['\x00'; N];
+                for (idx, ch) in word.chars().enumerate() {
+                    chars[idx] = ch;
+                }
+                (chars, chars_count(word.chars()))
+            })
+            .collect();
+        let cells = if N <= 5 {
+            Cells::U8(build_cells(&words, &answers, |code| code as u8))
+        } else if N <= Self::MAX_WORD_LEN {
+            Cells::U16(build_cells(&words, &answers, |code| code as u16))
+        } else {
+            return Err(format!(
+                "can't precompute patterns for {}-letter words (at most {} letters)",
+                N,
+                Self::MAX_WORD_LEN
+            ));
+        };
+        Ok(PatternMatrix {
+            index: Self::index_words(&words),
+            len,
+            cells,
+            hash: words_hash::<N>(&words),
+        })
+    }
+
+    /// Like `build`, but if `cache_dir` is given, first looks for a matrix that was previously
+    /// saved there for the same word list. If there isn't one (or it's unreadable), this builds the
+    /// matrix and saves it for next time. The cache is only there to save time, so problems with it
+    /// are just warnings, on stderr.
+    pub fn load_or_build(words: &WordList<N>, cache_dir: Option<&Path>) -> Result<Self, String> {
+        let Some(cache_dir) = cache_dir else {
+            return Self::build(words);
+        };
+        let word_strs: Vec<&str> = words.words().map(|wf| wf.word.as_str()).collect();
+        let path = Self::cache_path(cache_dir, words_hash::<N>(&word_strs));
+        match fs::read(&path) {
+            Ok(bytes) => {
+                if let Some(matrix) = Self::from_cache_bytes(&word_strs, &bytes) {
+                    return Ok(matrix);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => eprintln!("warning: couldn't read {}: {}", path.display(), e),
+        }
+        let matrix = Self::build(words)?;
+        if let Err(e) =
+            fs::create_dir_all(cache_dir).and_then(|_| fs::write(&path, matrix.to_cache_bytes()))
+        {
+            eprintln!("warning: couldn't write {}: {}", path.display(), e);
+        }
+        Ok(matrix)
+    }
+
+    /// The number of words in the matrix.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Where `word` is in the matrix, if it's there at all.
+    pub(super) fn index_of(&self, word: &str) -> Option<usize> {
+        self.index.get(word).copied()
+    }
+
+    /// The patterns that `guess` gets against each word in the matrix, if `guess` is in it.
+    pub(super) fn row(&self, guess: &str) -> Option<PatternRow<'_>> {
+        let idx = self.index_of(guess)?;
+        let len = self.len();
+        let range = idx * len..(idx + 1) * len;
+        Some(match &self.cells {
+            Cells::U8(cells) => PatternRow::U8(&cells[range]),
+            Cells::U16(cells) => PatternRow::U16(&cells[range]),
+        })
+    }
+
+    fn index_words(words: &[&str]) -> HashMap<String, usize> {
+        words
+            .iter()
+            .enumerate()
+            .map(|(idx, word)| (word.to_string(), idx))
+            .collect()
+    }
+
+    fn cache_path(cache_dir: &Path, hash: u64) -> PathBuf {
+        cache_dir.join(format!("patterns-{}-{:016x}.bin", N, hash))
+    }
+
+    /// The cache file format is: the magic bytes, then the format version, `N`, the number of
+    /// words, and the word list's hash; and then the cells, row by row. All numbers are
+    /// little-endian.
+    fn to_cache_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(CACHE_MAGIC);
+        bytes.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(N as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.hash.to_le_bytes());
+        match &self.cells {
+            Cells::U8(cells) => bytes.extend_from_slice(cells),
+            Cells::U16(cells) => cells
+                .iter()
+                .for_each(|c| bytes.extend_from_slice(&c.to_le_bytes())),
+        }
+        bytes
+    }
+
+    fn from_cache_bytes(words: &[&str], bytes: &[u8]) -> Option<Self> {
+        let hash = words_hash::<N>(words);
+        let mut expected_header = Vec::new();
+        expected_header.extend_from_slice(CACHE_MAGIC);
+        expected_header.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+        expected_header.extend_from_slice(&(N as u32).to_le_bytes());
+        expected_header.extend_from_slice(&(words.len() as u32).to_le_bytes());
+        expected_header.extend_from_slice(&hash.to_le_bytes());
+        let body = bytes.strip_prefix(expected_header.as_slice())?;
+
+        let num_cells = words.len() * words.len();
+        let cells = if N <= 5 {
+            (body.len() == num_cells).then(|| Cells::U8(body.to_vec()))?
+        } else {
+            (body.len() == num_cells * 2).then(|| {
+                let cells = body
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect();
+                Cells::U16(cells)
+            })?
+        };
+        Some(PatternMatrix {
+            index: Self::index_words(words),
+            len: words.len(),
+            cells,
+            hash,
+        })
+    }
+}
+
+impl<'a> PatternRow<'a> {
+    /// Calls `action` with the pattern code for each of the given answer indexes, in order.
+    #[inline]
+    pub(super) fn for_each<F: FnMut(usize, usize)>(&self, answer_indexes: &[usize], action: F) {
+        fn for_each_in<T: Copy + Into<usize>, F: FnMut(usize, usize)>(
+            cells: &[T],
+            answer_indexes: &[usize],
+            mut action: F,
+        ) {
+            for (i, answer_idx) in answer_indexes.iter().enumerate() {
+                action(i, cells[*answer_idx].into());
+            }
+        }
+        match self {
+            PatternRow::U8(cells) => for_each_in(cells, answer_indexes, action),
+            PatternRow::U16(cells) => for_each_in(cells, answer_indexes, action),
+        }
+    }
+}
+
+/// Computes the cells for `build`, row by row (that is, guess by guess) in parallel.
+fn build_cells<const N: usize, T, F>(
+    guesses: &[&str],
+    answers: &[([char; N], CharsCount)],
+    to_cell: F,
+) -> Vec<T>
+where
+    T: Copy + Default + Send,
+    F: Fn(usize) -> T + Sync,
+{
+    let len = answers.len();
+    let mut cells = vec![T::default(); len * len];
+    cells
+        .par_chunks_mut(len.max(1))
+        .zip(guesses.par_iter())
+        .for_each(|(row, guess)| {
+            for (cell, (chars, counts)) in row.iter_mut().zip(answers) {
+                let mut counts = *counts;
+                *cell = to_cell(Pattern::score_counted(guess, chars, &mut counts).code());
+            }
+        });
+    cells
+}
+
+/// A hash of the words (in order) and their length, for naming cache files. This is FNV-1a, rather
+/// than std's `DefaultHasher`, because it needs to be the same from one build to the next.
+fn words_hash<const N: usize>(words: &[&str]) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    let mut hash = FNV_OFFSET;
+    let n_bytes = (N as u64).to_le_bytes();
+    for byte in n_bytes
+        .iter()
+        .chain(words.iter().flat_map(|w| w.as_bytes().iter().chain(b"\n")))
+    {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::pattern::AnswerPatterns;
+    use std::sync::Arc;
+
+    #[test]
+    fn matrix_matches_computed_patterns() {
+        let words: WordList<5> = WordList::std_with_limit(200).unwrap();
+        let with_matrix = words
+            .clone()
+            .with_pattern_matrix(Arc::new(PatternMatrix::build(&words).unwrap()));
+        let computed = AnswerPatterns::new(&words);
+        let from_matrix = AnswerPatterns::new(&with_matrix);
+        for guess in words.words() {
+            let mut expected = Vec::new();
+            computed.for_each(&guess.word, |code, _| expected.push(code));
+            let mut actual = Vec::new();
+            from_matrix.for_each(&guess.word, |code, _| actual.push(code));
+            assert_eq!(expected, actual, "{}", guess.word);
+        }
+    }
+
+    #[test]
+    fn cache_round_trip() {
+        let words: WordList<5> = WordList::from_words(&["CRANE", "SLATE", "EERIE"]);
+        let word_strs: Vec<&str> = words.words().map(|wf| wf.word.as_str()).collect();
+        let matrix = PatternMatrix::build(&words).unwrap();
+        let bytes = matrix.to_cache_bytes();
+
+        let loaded = PatternMatrix::<5>::from_cache_bytes(&word_strs, &bytes).unwrap();
+        let Cells::U8(expected) = &matrix.cells else {
+            panic!("expected u8 cells")
+        };
+        let Cells::U8(actual) = &loaded.cells else {
+            panic!("expected u8 cells")
+        };
+        assert_eq!(expected, actual);
+
+        // A different word list (or a truncated file) doesn't match.
+        assert!(PatternMatrix::<5>::from_cache_bytes(&["CRANE", "SLATE"], &bytes).is_none());
+        assert!(
+            PatternMatrix::<5>::from_cache_bytes(&word_strs, &bytes[..bytes.len() - 1]).is_none()
+        );
+    }
+
+    #[test]
+    fn size_cap() {
+        assert!(PatternMatrix::<5>::fits(7500));
+        assert!(PatternMatrix::<5>::fits(12000));
+        // Longer words take two bytes per pattern.
+        assert!(!PatternMatrix::<6>::fits(12000));
+        assert!(!PatternMatrix::<5>::fits(100_000));
+        assert!(!PatternMatrix::<5>::fits(usize::MAX));
+    }
+
+    /// A cache that can't be written to still gives a matrix: it's just not saved.
+    #[test]
+    fn unwritable_cache() {
+        let words: WordList<5> = WordList::from_words(&["CRANE", "SLATE"]);
+        // A directory can't be created under a file.
+        let file = std::env::temp_dir().join(format!("wordlehelper-cache-{}", std::process::id()));
+        fs::write(&file, b"").unwrap();
+        let matrix = PatternMatrix::load_or_build(&words, Some(&file.join("cache")));
+        fs::remove_file(&file).unwrap();
+        assert_eq!(2, matrix.unwrap().len());
+    }
+}
@@ -10,7 +10,11 @@ impl<const N: usize> Analyzer<N> for CharPositionScorer<N> {
         "Char-Pos".to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
+    fn analyze<'a>(
+        &self,
+        _guesses: &'a WordList<N>,
+        words_list: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
         // First, we want the individual char scores, per position. Each one of those is basically
         // "how close does this char in this position get to cutting all the words into even
         // thirds?"
@@ -24,9 +28,9 @@ impl<const N: usize> Analyzer<N> for CharPositionScorer<N> {
             let word_chars = uniq_chars(word);
             for (idx, word_char) in word.chars().enumerate() {
                 for guess_char in &all_chars {
-                    let mut counts = &mut position_counts[idx]
+                    let counts = &mut position_counts[idx]
                         .entry(*guess_char)
-                        .or_insert_with(|| CharPosCounts::default());
+                        .or_insert_with(CharPosCounts::default);
                     if guess_char == &word_char {
                         counts.correct += 1;
                     } else if word_chars.contains(*guess_char) {
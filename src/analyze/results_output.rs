@@ -0,0 +1,133 @@
+use crate::analyze::auto_guesser::{GuessResult, ResultsByWord};
+use crate::guess::guesses::GuessStr;
+use serde::Serialize;
+use std::io;
+use std::io::Write;
+
+/// A single game (one answer, one analyzer), flattened for machine-readable output.
+#[derive(Serialize)]
+struct GameRecord<'a, const N: usize> {
+    answer: &'a str,
+    analyzer: &'a str,
+    result: &'a GuessResult,
+    guesses: &'a [GuessStr<N>],
+}
+
+fn game_records<const N: usize>(
+    results: &[ResultsByWord<N>],
+) -> impl Iterator<Item = GameRecord<'_, N>> {
+    results.iter().flat_map(|by_word| {
+        by_word
+            .analyzer_results
+            .iter()
+            .map(move |analyzer_result| GameRecord {
+                answer: &by_word.answer,
+                analyzer: &analyzer_result.name,
+                result: &analyzer_result.result,
+                guesses: &analyzer_result.guesses,
+            })
+    })
+}
+
+/// Writes one JSON object per game, one per line.
+pub fn write_json_lines<const N: usize, W: Write>(
+    results: &[ResultsByWord<N>],
+    out: &mut W,
+) -> io::Result<()> {
+    for record in game_records(results) {
+        serde_json::to_writer(&mut *out, &record)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Writes one CSV row per game, with a header row. The guesses are all in one column, as
+/// space-separated `WORD:FEEDBACK` pairs (see `GuessStr::feedback`).
+pub fn write_csv<const N: usize, W: Write>(
+    results: &[ResultsByWord<N>],
+    out: &mut W,
+) -> io::Result<()> {
+    writeln!(out, "answer,analyzer,result,guess_count,guesses")?;
+    for record in game_records(results) {
+        let guesses: Vec<String> = record
+            .guesses
+            .iter()
+            .map(|g| format!("{}:{}", g.word(), g.feedback()))
+            .collect();
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            csv_field(record.answer),
+            csv_field(record.analyzer),
+            record.result,
+            record.guesses.len(),
+            csv_field(&guesses.join(" ")),
+        )?;
+    }
+    Ok(())
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::auto_guesser::AnalyzerGuessResult;
+    use crate::guess::known_word_constraints::CharKnowledge;
+
+    #[test]
+    fn json_lines() {
+        let mut out = Vec::new();
+        write_json_lines(&results(), &mut out).unwrap();
+
+        let expect = concat!(
+            r#"{"answer":"CRANE","analyzer":"Entropy (freq-weighted)","result":"Success","#,
+            r#""guesses":[{"word":"CRATE","feedback":"GGGBG"},{"word":"CRANE","feedback":"GGGGG"}]}"#,
+            "\n"
+        );
+        assert_eq!(expect, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn csv() {
+        let mut out = Vec::new();
+        write_csv(&results(), &mut out).unwrap();
+
+        let expect = concat!(
+            "answer,analyzer,result,guess_count,guesses\n",
+            "CRANE,Entropy (freq-weighted),Success,2,CRATE:GGGBG CRANE:GGGGG\n",
+        );
+        assert_eq!(expect, String::from_utf8(out).unwrap());
+    }
+
+    fn results() -> Vec<ResultsByWord<5>> {
+        vec![ResultsByWord {
+            answer: "CRANE".to_string(),
+            analyzer_results: vec![AnalyzerGuessResult {
+                name: "Entropy (freq-weighted)".to_string(),
+                result: GuessResult::Success,
+                guesses: vec![guess_str("CRATE", "GGGBG"), guess_str("CRANE", "GGGGG")],
+            }],
+        }]
+    }
+
+    fn guess_str(word: &str, feedback: &str) -> GuessStr<5> {
+        let mut result = GuessStr::new();
+        for (idx, (ch, fb)) in word.chars().zip(feedback.chars()).enumerate() {
+            let guess_ch = result.guess_mut(idx);
+            guess_ch.set_ch(ch);
+            guess_ch.set_knowledge(match fb {
+                'G' => CharKnowledge::Correct,
+                'Y' => CharKnowledge::WrongPosition,
+                _ => CharKnowledge::Missing,
+            });
+        }
+        result
+    }
+}
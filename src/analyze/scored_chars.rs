@@ -36,9 +36,13 @@ impl<const N: usize> Analyzer<N> for CharScorer<N> {
         .to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
+    fn analyze<'a>(
+        &self,
+        _guesses: &'a WordList<N>,
+        words_list: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
         let char_counts = CharCounts::new(words_list);
-        let scorer = ScoredChars::new(&words_list, &char_counts, self.double_count_freq);
+        let scorer = ScoredChars::new(words_list, &char_counts, self.double_count_freq);
         scorer.all_word_scores()
     }
 }
@@ -99,7 +103,7 @@ impl<'a, 'b, const N: usize> ScoredChars<'a, 'b, N> {
                 score += all_char_scores.get(&ch).unwrap_or(&0.0)
             }
             if self.char_score_includes_frequency {
-                score *= word_freq.freq as f64;
+                score *= word_freq.freq;
             }
             result.push(ScoredWord { word, score });
         }
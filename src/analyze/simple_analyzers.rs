@@ -5,6 +5,7 @@ use crate::word_list::{WordFreq, WordList};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 
+#[allow(dead_code)]
 pub struct AlphabeticalOrder {
     pub ascending: bool,
 }
@@ -19,7 +20,11 @@ impl<const N: usize> Analyzer<N> for AlphabeticalOrder {
         .to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
+    fn analyze<'a>(
+        &self,
+        _guesses: &'a WordList<N>,
+        words_list: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
         let mut words: Vec<ScoredWord<'a>> = words_list
             .words()
             .map(|w| ScoredWord {
@@ -45,7 +50,11 @@ impl<const N: usize> Analyzer<N> for CharFrequencies {
         "Char Freqs".to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
+    fn analyze<'a>(
+        &self,
+        _guesses: &'a WordList<N>,
+        words_list: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
         let chars_count = util::chars_count(words_list.all_chars());
         words_list
             .words()
@@ -70,13 +79,14 @@ impl<const N: usize> Analyzer<N> for WordFrequencies {
         "Most Common Words".to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
+    fn analyze<'a>(
+        &self,
+        _guesses: &'a WordList<N>,
+        words_list: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
         words_list
             .words()
-            .map(|WordFreq { word, freq }| ScoredWord {
-                word,
-                score: *freq as f64,
-            })
+            .map(|WordFreq { word, freq }| ScoredWord { word, score: *freq })
             .collect()
     }
 }
@@ -88,7 +98,11 @@ impl<const N: usize> Analyzer<N> for Random {
         "Random".to_string()
     }
 
-    fn analyze<'a>(&self, words_list: &'a WordList<N>) -> Vec<ScoredWord<'a>> {
+    fn analyze<'a>(
+        &self,
+        _guesses: &'a WordList<N>,
+        words_list: &'a WordList<N>,
+    ) -> Vec<ScoredWord<'a>> {
         let mut result: Vec<&'a String> = words_list.words().map(|wf| &wf.word).collect();
         result.shuffle(&mut thread_rng());
         result
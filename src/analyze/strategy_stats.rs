@@ -0,0 +1,171 @@
+use crate::analyze::auto_guesser::{GuessResult, ResultsByWord};
+use std::cmp::Ordering;
+
+/// How well a single analyzer did across many games.
+pub struct AnalyzerSummary {
+    pub name: String,
+    pub games: usize,
+    /// `histogram[i]` is the number of games won in `i + 1` guesses.
+    pub histogram: Vec<usize>,
+    /// The answers that this analyzer failed to guess.
+    pub failures: Vec<String>,
+    /// The number of guesses each won game took, sorted ascending.
+    win_guess_counts: Vec<usize>,
+}
+
+impl AnalyzerSummary {
+    fn new(name: &str) -> Self {
+        AnalyzerSummary {
+            name: name.to_string(),
+            games: 0,
+            histogram: Vec::new(),
+            failures: Vec::new(),
+            win_guess_counts: Vec::new(),
+        }
+    }
+
+    pub fn wins(&self) -> usize {
+        self.win_guess_counts.len()
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins() as f64 / self.games as f64
+        }
+    }
+
+    /// The mean number of guesses, among the games that were won.
+    pub fn mean_guesses(&self) -> Option<f64> {
+        if self.win_guess_counts.is_empty() {
+            return None;
+        }
+        let total: usize = self.win_guess_counts.iter().sum();
+        Some(total as f64 / self.win_guess_counts.len() as f64)
+    }
+
+    /// The median number of guesses, among the games that were won.
+    pub fn median_guesses(&self) -> Option<f64> {
+        let counts = &self.win_guess_counts;
+        if counts.is_empty() {
+            return None;
+        }
+        let mid = counts.len() / 2;
+        if counts.len().is_multiple_of(2) {
+            Some((counts[mid - 1] + counts[mid]) as f64 / 2.0)
+        } else {
+            Some(counts[mid] as f64)
+        }
+    }
+
+    fn add(&mut self, answer: &str, result: &GuessResult, guesses: usize) {
+        self.games += 1;
+        match result {
+            GuessResult::Success => {
+                if self.histogram.len() < guesses {
+                    self.histogram.resize(guesses, 0);
+                }
+                self.histogram[guesses - 1] += 1;
+                self.win_guess_counts.push(guesses);
+            }
+            GuessResult::Failure => self.failures.push(answer.to_string()),
+        }
+    }
+
+    /// Best first: highest win rate, then fewest mean guesses, then name.
+    fn rank_cmp(&self, other: &Self) -> Ordering {
+        let mean = |s: &Self| s.mean_guesses().unwrap_or(f64::INFINITY);
+        other
+            .win_rate()
+            .total_cmp(&self.win_rate())
+            .then_with(|| mean(self).total_cmp(&mean(other)))
+            .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+/// Summarizes the results of many auto-guessed games, per analyzer. The summaries are ranked from
+/// best to worst.
+pub fn summarize<const N: usize>(results: &[ResultsByWord<N>]) -> Vec<AnalyzerSummary> {
+    let mut summaries: Vec<AnalyzerSummary> = Vec::new();
+    for by_word in results {
+        for analyzer_result in &by_word.analyzer_results {
+            let idx = match summaries
+                .iter()
+                .position(|s| s.name == analyzer_result.name)
+            {
+                Some(idx) => idx,
+                None => {
+                    summaries.push(AnalyzerSummary::new(&analyzer_result.name));
+                    summaries.len() - 1
+                }
+            };
+            summaries[idx].add(
+                &by_word.answer,
+                &analyzer_result.result,
+                analyzer_result.guesses.len(),
+            );
+        }
+    }
+    for summary in summaries.iter_mut() {
+        summary.win_guess_counts.sort();
+    }
+    summaries.sort_by(|a, b| a.rank_cmp(b));
+    summaries
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::auto_guesser::AnalyzerGuessResult;
+    use crate::guess::guesses::GuessStr;
+
+    #[test]
+    fn summarize_ranks_and_counts() {
+        let results = vec![
+            by_word("AAAAA", vec![("steady", Some(3)), ("flaky", Some(2))]),
+            by_word("BBBBB", vec![("steady", Some(4)), ("flaky", None)]),
+            by_word("CCCCC", vec![("steady", Some(3)), ("flaky", Some(6))]),
+        ];
+
+        let summaries = summarize(&results);
+        let names: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(vec!["steady", "flaky"], names);
+
+        let steady = &summaries[0];
+        assert_eq!(3, steady.games);
+        assert_eq!(3, steady.wins());
+        assert_eq!(vec![0, 0, 2, 1], steady.histogram);
+        assert_eq!(Some(10.0 / 3.0), steady.mean_guesses());
+        assert_eq!(Some(3.0), steady.median_guesses());
+        assert!(steady.failures.is_empty());
+
+        let flaky = &summaries[1];
+        assert_eq!(3, flaky.games);
+        assert_eq!(2, flaky.wins());
+        assert_eq!(vec![0, 1, 0, 0, 0, 1], flaky.histogram);
+        assert_eq!(Some(4.0), flaky.median_guesses());
+        assert_eq!(vec!["BBBBB".to_string()], flaky.failures);
+    }
+
+    fn by_word(answer: &str, results: Vec<(&str, Option<usize>)>) -> ResultsByWord<5> {
+        let analyzer_results = results
+            .into_iter()
+            .map(|(name, won_in)| {
+                let (result, guesses) = match won_in {
+                    Some(count) => (GuessResult::Success, count),
+                    None => (GuessResult::Failure, 6),
+                };
+                AnalyzerGuessResult {
+                    name: name.to_string(),
+                    result,
+                    guesses: (0..guesses).map(|_| GuessStr::new()).collect(),
+                }
+            })
+            .collect();
+        ResultsByWord {
+            answer: answer.to_string(),
+            analyzer_results,
+        }
+    }
+}
@@ -25,13 +25,20 @@ pub struct CharsCount {
 }
 
 impl CharsCount {
+    /// A count of `count` for every char.
+    pub fn with_all(count: u32) -> Self {
+        CharsCount {
+            counts: [count; NUM_CHARS],
+        }
+    }
+
     #[inline]
     pub fn get(&self, ch: char) -> u32 {
         if !ch.is_ascii_alphabetic() {
             return 0;
         }
         let ch = ch.to_ascii_uppercase();
-        return self.counts[ch as usize - A_USIZE];
+        self.counts[ch as usize - A_USIZE]
     }
 
     #[inline]
@@ -40,7 +47,7 @@ impl CharsCount {
             return None;
         }
         let ch = ch.to_ascii_uppercase();
-        return Some(&mut self.counts[ch as usize - A_USIZE]);
+        Some(&mut self.counts[ch as usize - A_USIZE])
     }
 
     #[inline]
@@ -61,6 +68,16 @@ impl CharsCount {
     pub fn reset_all(&mut self) {
         self.counts.fill(0);
     }
+
+    /// Whether each char's count is at least its count in `min`, and no more than its count in
+    /// `max`.
+    #[inline]
+    pub fn is_between(&self, min: &CharsCount, max: &CharsCount) -> bool {
+        self.counts
+            .iter()
+            .zip(min.counts.iter().zip(max.counts.iter()))
+            .all(|(count, (min, max))| min <= count && count <= max)
+    }
 }
 
 pub struct CharsSet {
@@ -0,0 +1,252 @@
+use crate::word_list::{WordFreq, WordList};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How a word list file is laid out.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordsFileFormat {
+    /// `word<TAB>freq` on each line, like the embedded lists. Words of other lengths are skipped.
+    Freq,
+    /// One word per line, most common first. Since there are no frequencies, each word's is taken
+    /// from its rank (the `n`th word gets `1/n`). Words of other lengths are skipped.
+    Plain,
+    /// The official list of Wordle answers: one word per line, in no particular order. Every word
+    /// is equally likely, and every word must be the right length.
+    Answers,
+}
+
+/// A word list file to load at runtime, and how much weight to give it when combining it with the
+/// other lists (see `WordList::combine`).
+///
+/// It's written `[FORMAT:]PATH[=WEIGHT]`: for example, `answers:wordle.txt=2`. If there's no
+/// format, it's `freq` if the file has any tabs, or `plain` otherwise. The weight defaults to 1.
+#[derive(Clone, PartialEq, Debug)]
+pub struct WordsFileSpec {
+    pub path: PathBuf,
+    pub format: Option<WordsFileFormat>,
+    pub weight: f64,
+}
+
+/// Reads and parses a word list file. If `format` is `None`, it's detected from the file: `Freq` if
+/// the file has any tabs, or `Plain` otherwise. Errors include the path and (for invalid lines) the
+/// line number.
+pub fn load_words_file<const N: usize>(
+    path: &Path,
+    format: Option<WordsFileFormat>,
+) -> Result<WordList<N>, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+    let format = format.unwrap_or_else(|| WordsFileFormat::detect(&text));
+    parse_words::<N>(&text, format)
+        .map(WordList::new)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+impl FromStr for WordsFileSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, rest) = match s.split_once(':') {
+            Some((prefix, rest)) => match WordsFileFormat::from_str(prefix) {
+                Ok(format) => (Some(format), rest),
+                Err(_) => (None, s),
+            },
+            None => (None, s),
+        };
+        let (path, weight) = split_weight(rest)?;
+        if path.is_empty() {
+            return Err(format!("no path in {}", s));
+        }
+        Ok(WordsFileSpec {
+            path: PathBuf::from(path),
+            format,
+            weight,
+        })
+    }
+}
+
+/// Splits an optional `=WEIGHT` off the end of `s`. The weight defaults to 1. Anything after the last
+/// `=` that isn't a number isn't a weight at all, but part of `s`: paths can have `=`s in them.
+pub(crate) fn split_weight(s: &str) -> Result<(&str, f64), String> {
+    let Some((rest, weight_str)) = s.rsplit_once('=') else {
+        return Ok((s, 1.0));
+    };
+    match weight_str.parse::<f64>() {
+        Ok(weight) if weight.is_finite() && weight >= 0.0 => Ok((rest, weight)),
+        Err(_) => Ok((s, 1.0)),
+        Ok(_) => Err(format!(
+            "invalid weight \"{}\" in {} (it must be a number, zero or more)",
+            weight_str, s
+        )),
+    }
+}
+
+impl WordsFileFormat {
+    fn detect(text: &str) -> Self {
+        if text.contains('\t') {
+            WordsFileFormat::Freq
+        } else {
+            WordsFileFormat::Plain
+        }
+    }
+}
+
+impl FromStr for WordsFileFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "freq" => Ok(WordsFileFormat::Freq),
+            "plain" => Ok(WordsFileFormat::Plain),
+            "answers" => Ok(WordsFileFormat::Answers),
+            other => Err(format!("unknown word list format: {}", other)),
+        }
+    }
+}
+
+/// Parses a word list in the given format, most common words first. Blank lines, and lines
+/// starting with `#`, are ignored. Words are uppercased.
+pub fn parse_words<const N: usize>(
+    text: &str,
+    format: WordsFileFormat,
+) -> Result<Vec<WordFreq>, String> {
+    let mut words = Vec::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_err = |msg: String| format!("line {}: {}", line_idx + 1, msg);
+        let (word, freq) = match format {
+            WordsFileFormat::Freq => {
+                let Some((word, freq_str)) = line.split_once('\t') else {
+                    return Err(line_err(format!("expected word<TAB>freq: {}", line)));
+                };
+                let freq = match freq_str.trim().parse::<f64>() {
+                    Ok(freq) if freq.is_finite() && freq >= 0.0 => freq,
+                    _ => return Err(line_err(format!("invalid frequency: {}", freq_str))),
+                };
+                (word.trim(), freq)
+            }
+            WordsFileFormat::Plain | WordsFileFormat::Answers => (line, 1.0),
+        };
+        if let Some(bad) = word.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(line_err(format!("'{}' isn't a letter: {}", bad, word)));
+        }
+        if word.len() != N {
+            if format == WordsFileFormat::Answers {
+                return Err(line_err(format!("expected a {}-letter word: {}", N, word)));
+            }
+            continue;
+        }
+        words.push(WordFreq {
+            word: word.to_ascii_uppercase(),
+            freq,
+        });
+    }
+
+    match format {
+        WordsFileFormat::Freq => {
+            words.sort_by(|a, b| b.freq.total_cmp(&a.freq));
+        }
+        WordsFileFormat::Plain => {
+            for (rank, word) in words.iter_mut().enumerate() {
+                word.freq = 1.0 / (rank + 1) as f64;
+            }
+        }
+        WordsFileFormat::Answers => {
+            words.sort_by(|a, b| a.word.cmp(&b.word));
+            words.dedup_by(|a, b| a.word == b.word);
+        }
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_spec() {
+        assert_eq!(
+            WordsFileSpec {
+                path: PathBuf::from("wordle.txt"),
+                format: Some(WordsFileFormat::Answers),
+                weight: 2.5,
+            },
+            "answers:wordle.txt=2.5".parse().unwrap()
+        );
+        assert_eq!(
+            WordsFileSpec {
+                path: PathBuf::from("C:\\words.txt"),
+                format: None,
+                weight: 1.0,
+            },
+            "C:\\words.txt".parse().unwrap()
+        );
+        assert_eq!(
+            WordsFileSpec {
+                path: PathBuf::from("dir/a=b.txt"),
+                format: None,
+                weight: 1.0,
+            },
+            "dir/a=b.txt".parse().unwrap()
+        );
+        assert_eq!(
+            WordsFileSpec {
+                path: PathBuf::from("dir/a=b.txt"),
+                format: None,
+                weight: 3.0,
+            },
+            "dir/a=b.txt=3".parse().unwrap()
+        );
+        assert!("words.txt=-1".parse::<WordsFileSpec>().is_err());
+        assert!("freq:".parse::<WordsFileSpec>().is_err());
+    }
+
+    #[test]
+    fn parse_freq() {
+        let words = parse_words::<5>(
+            "# comment\nhello\t3\n\nfoo\t10\nworld\t7.5\n",
+            WordsFileFormat::Freq,
+        )
+        .unwrap();
+        let words: Vec<(&str, f64)> = words.iter().map(|w| (w.word.as_str(), w.freq)).collect();
+        assert_eq!(vec![("WORLD", 7.5), ("HELLO", 3.0)], words);
+    }
+
+    #[test]
+    fn parse_plain() {
+        let words = parse_words::<5>("crane\nslate\nab\ntrace\n", WordsFileFormat::Plain).unwrap();
+        let words: Vec<(&str, f64)> = words.iter().map(|w| (w.word.as_str(), w.freq)).collect();
+        assert_eq!(
+            vec![("CRANE", 1.0), ("SLATE", 0.5), ("TRACE", 1.0 / 3.0)],
+            words
+        );
+    }
+
+    #[test]
+    fn parse_answers() {
+        let words = parse_words::<5>("slate\ncrane\nslate\n", WordsFileFormat::Answers).unwrap();
+        let words: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(vec!["CRANE", "SLATE"], words);
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let err = parse_words::<5>("crane\nslate 3\n", WordsFileFormat::Freq).unwrap_err();
+        assert_eq!("line 1: expected word<TAB>freq: crane", err);
+
+        let err = parse_words::<5>("crane\t1\nslate\tlots\n", WordsFileFormat::Freq).unwrap_err();
+        assert_eq!("line 2: invalid frequency: lots", err);
+
+        let err = parse_words::<5>("crane\n\nsl8te\n", WordsFileFormat::Plain).unwrap_err();
+        assert_eq!("line 3: '8' isn't a letter: sl8te", err);
+
+        let err = parse_words::<5>("crane\ncranes\n", WordsFileFormat::Answers).unwrap_err();
+        assert_eq!("line 2: expected a 5-letter word: cranes", err);
+    }
+}
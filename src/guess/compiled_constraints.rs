@@ -0,0 +1,139 @@
+use crate::analyze::util::{chars_count, CharsCount};
+
+/// The bit for any char that isn't an ASCII letter. Words shouldn't have any, but if they do, we
+/// treat them all as one "other" char.
+const OTHER_BIT: u32 = 26;
+
+/// All of the bits that an allowed-chars mask can have set: one per letter, plus `OTHER_BIT`.
+const ALL_CHARS_MASK: u32 = (1 << (OTHER_BIT + 1)) - 1;
+
+/// A word, encoded for `CompiledConstraints`. `WordList` precomputes these for all of its words,
+/// so that filtering doesn't need to look at the strings at all.
+#[derive(Clone)]
+pub struct EncodedWord<const N: usize> {
+    /// Each char's bit index: 0 for 'A' through 25 for 'Z', or `OTHER_BIT`.
+    bits: [u8; N],
+    counts: CharsCount,
+}
+
+impl<const N: usize> EncodedWord<N> {
+    pub fn new(word: &str) -> Self {
+        let mut bits = [OTHER_BIT as u8; N];
+        for (bit, ch) in bits.iter_mut().zip(word.chars()) {
+            if ch.is_ascii_alphabetic() {
+                *bit = ch.to_ascii_uppercase() as u8 - b'A';
+            }
+        }
+        EncodedWord {
+            bits,
+            counts: chars_count(word.chars()),
+        }
+    }
+}
+
+/// A form of `KnownWordConstraints` that's quick to check words against: each position has a mask
+/// of the letters allowed there, and each letter has a minimum and maximum count. Checking a word
+/// is then just a few bitwise ANDs and comparisons, with no allocation. See
+/// `KnownWordConstraints::compile`.
+pub struct CompiledConstraints<const N: usize> {
+    allowed: [u32; N],
+    at_least: CharsCount,
+    no_more_than: CharsCount,
+}
+
+impl<const N: usize> CompiledConstraints<N> {
+    /// Constraints that allow every word.
+    pub(crate) fn allow_all() -> Self {
+        CompiledConstraints {
+            allowed: [ALL_CHARS_MASK; N],
+            at_least: CharsCount::default(),
+            no_more_than: CharsCount::with_all(u32::MAX),
+        }
+    }
+
+    /// Allows only `ch` at `idx`.
+    pub(crate) fn require(&mut self, idx: usize, ch: char) {
+        self.allowed[idx] &= char_bit(ch);
+    }
+
+    /// Disallows `ch` at `idx`.
+    pub(crate) fn forbid(&mut self, idx: usize, ch: char) {
+        self.allowed[idx] &= !char_bit(ch);
+    }
+
+    /// Requires the word to have between `at_least` and `no_more_than` (inclusive) of `ch`.
+    pub(crate) fn limit_count(&mut self, ch: char, at_least: u32, no_more_than: Option<u32>) {
+        if let Some(count) = self.at_least.get_mut(ch) {
+            *count = (*count).max(at_least);
+        }
+        if let (Some(count), Some(no_more_than)) = (self.no_more_than.get_mut(ch), no_more_than) {
+            *count = (*count).min(no_more_than);
+        }
+    }
+
+    #[inline]
+    pub fn is_word_possible(&self, word: &EncodedWord<N>) -> bool {
+        self.allowed
+            .iter()
+            .zip(word.bits.iter())
+            .all(|(allowed, bit)| allowed & (1 << bit) != 0)
+            && word.counts.is_between(&self.at_least, &self.no_more_than)
+    }
+}
+
+fn char_bit(ch: char) -> u32 {
+    if ch.is_ascii_alphabetic() {
+        1 << (ch.to_ascii_uppercase() as u32 - 'A' as u32)
+    } else {
+        1 << OTHER_BIT
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::guess::guesses::GuessGrid;
+    use crate::guess::known_word_constraints::KnownWordConstraints;
+    use crate::word_list::WordList;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    /// The compiled constraints accept exactly the same words as the originals, including in hard
+    /// mode.
+    #[test]
+    fn compiled_matches_uncompiled() {
+        let words: Vec<String> = WordList::<5>::std_with_limit(1000)
+            .unwrap()
+            .words()
+            .map(|wf| wf.word.clone())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(0xB175);
+        for _ in 0..200 {
+            let answer = words.choose(&mut rng).unwrap();
+            let mut grid: GuessGrid<5> = GuessGrid::new(6);
+            for (idx, guess) in words.choose_multiple(&mut rng, 2).enumerate() {
+                grid.guess_mut(idx).set_scored(guess, answer);
+            }
+            let constraints = KnownWordConstraints::from_grid(&grid);
+            for constraints in [constraints.hard_mode(), constraints] {
+                let compiled = constraints.compile();
+                for word in words.choose_multiple(&mut rng, 50) {
+                    assert_eq!(
+                        constraints.is_word_possible(word),
+                        compiled.is_word_possible(&EncodedWord::new(word)),
+                        "{}",
+                        word
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_constraints_allow_everything() {
+        let compiled = KnownWordConstraints::<5>::empty().compile();
+        assert!(compiled.is_word_possible(&EncodedWord::new("FUZZY")));
+        assert!(compiled.is_word_possible(&EncodedWord::new("A-B'C")));
+    }
+}
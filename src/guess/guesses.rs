@@ -1,7 +1,12 @@
-use std::array::IntoIter;
 use std::slice::Iter;
+use std::str::FromStr;
+use std::vec::IntoIter;
 
 use crate::guess::known_word_constraints::CharKnowledge;
+use crate::guess::pattern::Pattern;
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct GuessChar {
@@ -20,7 +25,7 @@ impl GuessChar {
 
     /// Sets the `CharKnowledge` for this guess, as long as it has some `char`.
     pub fn set_knowledge(&mut self, knowledge: CharKnowledge) {
-        if let Some(_) = self.ch {
+        if self.ch.is_some() {
             self.knowledge = knowledge;
         }
     }
@@ -51,6 +56,7 @@ impl GuessChar {
     }
 }
 
+#[derive(Clone)]
 pub struct GuessStr<const N: usize> {
     guesses: [GuessChar; N],
 }
@@ -63,7 +69,7 @@ impl<const N: usize> GuessStr<N> {
     }
 
     pub fn chars(&self) -> Iter<'_, GuessChar> {
-        return self.guesses.iter();
+        self.guesses.iter()
     }
 
     pub fn guesses(&self) -> &[GuessChar; N] {
@@ -74,31 +80,154 @@ impl<const N: usize> GuessStr<N> {
         &mut self.guesses[idx]
     }
 
+    /// The guessed word, with a space for any position that doesn't have a char yet.
+    pub fn word(&self) -> String {
+        self.chars().map(|c| c.ch().unwrap_or(' ')).collect()
+    }
+
+    /// The feedback for each char, as given by `CharKnowledge::feedback_char`.
+    pub fn feedback(&self) -> String {
+        self.chars()
+            .map(|c| c.knowledge().feedback_char())
+            .collect()
+    }
+
+    /// Sets this row to `guess`, with the feedback that it would get if the answer were `answer`
+    /// (see `Pattern::score`).
+    pub fn set_scored(&mut self, guess: &str, answer: &str) {
+        for (idx, ch) in guess.chars().take(N).enumerate() {
+            let guess_ch = self.guess_mut(idx);
+            guess_ch.unset_ch(); // so that set_ch always resets the knowledge
+            guess_ch.set_ch(ch);
+        }
+        self.set_pattern(&Pattern::score(guess, answer));
+    }
+
+    /// Applies `pattern`'s knowledge to this row's chars, which must already be set.
+    pub fn set_pattern(&mut self, pattern: &Pattern<N>) {
+        for (guess_ch, knowledge) in self.guesses.iter_mut().zip(pattern.knowledge()) {
+            guess_ch.set_knowledge(*knowledge);
+        }
+    }
+
+    /// This row's feedback, as a `Pattern`.
+    pub fn pattern(&self) -> Pattern<N> {
+        let mut knowledge = [CharKnowledge::Unknown; N];
+        for (k, guess_ch) in knowledge.iter_mut().zip(self.chars()) {
+            *k = guess_ch.knowledge();
+        }
+        Pattern::new(knowledge)
+    }
+
+    /// Parses a feedback string (see `Pattern`'s `FromStr`) and applies it to this row's chars,
+    /// which must already be set.
+    pub fn set_feedback(&mut self, feedback: &str) -> Result<(), String> {
+        self.set_pattern(&feedback.parse()?);
+        Ok(())
+    }
+
     fn is_fully_filled(&self) -> bool {
         self.chars()
             .all(|&GuessChar { ch, knowledge }| ch.is_some() && knowledge != CharKnowledge::Unknown)
     }
 }
 
+/// Serializes as a `word` and a `feedback` string; see `GuessStr::word` and `GuessStr::feedback`.
+impl<const N: usize> Serialize for GuessStr<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("GuessStr", 2)?;
+        state.serialize_field("word", &self.word())?;
+        state.serialize_field("feedback", &self.feedback())?;
+        state.end()
+    }
+}
+
+/// The inverse of the `Serialize` impl. Unlike parsing, this allows a partly filled-in row: any
+/// spaces in the word are chars that haven't been entered yet.
+impl<'de, const N: usize> Deserialize<'de> for GuessStr<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            word: String,
+            feedback: String,
+        }
+        let Fields { word, feedback } = Fields::deserialize(deserializer)?;
+        if word.chars().count() != N {
+            return Err(D::Error::custom(format!(
+                "expected a {}-letter word, but got \"{}\"",
+                N, word
+            )));
+        }
+        let mut result = Self::new();
+        for (idx, ch) in word.chars().enumerate() {
+            if ch != ' ' && !result.guesses[idx].set_ch(ch) {
+                return Err(D::Error::custom(format!(
+                    "invalid letter '{}' in \"{}\"",
+                    ch, word
+                )));
+            }
+        }
+        result.set_feedback(&feedback).map_err(D::Error::custom)?;
+        Ok(result)
+    }
+}
+
+/// Parses a guess and its feedback, separated by a colon: for example, `CRANE:BYGBB` or
+/// `CRANE:⬛🟨🟩⬛⬛`.
+impl<const N: usize> FromStr for GuessStr<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((word, feedback)) = s.split_once(':') else {
+            return Err(format!(
+                "expected a guess and its feedback, like CRANE:BYGBB (got \"{}\")",
+                s
+            ));
+        };
+        let word = word.trim();
+        if word.chars().count() != N {
+            return Err(format!(
+                "expected a {}-letter word, but got \"{}\"",
+                N, word
+            ));
+        }
+        let mut result = Self::new();
+        for (idx, ch) in word.chars().enumerate() {
+            if !result.guesses[idx].set_ch(ch) {
+                return Err(format!("invalid letter '{}' in \"{}\"", ch, word));
+            }
+        }
+        result.set_feedback(feedback.trim())?;
+        Ok(result)
+    }
+}
+
 impl<const N: usize> Default for GuessStr<N> {
     fn default() -> Self {
         GuessStr::new()
     }
 }
 
-pub struct GuessGrid<const N: usize, const R: usize> {
-    guesses: [GuessStr<N>; R],
+/// The rows of a game: one per guess allowed, whether or not it's been guessed yet.
+pub struct GuessGrid<const N: usize> {
+    guesses: Vec<GuessStr<N>>,
 }
 
-impl<const N: usize, const R: usize> GuessGrid<N, R> {
-    pub fn new() -> Self {
+impl<const N: usize> GuessGrid<N> {
+    /// An empty grid with `row_count` rows.
+    pub fn new(row_count: usize) -> Self {
         GuessGrid {
-            guesses: init_array(),
+            guesses: vec![GuessStr::new(); row_count],
         }
     }
 
+    /// How many guesses the game allows.
+    pub fn row_count(&self) -> usize {
+        self.guesses.len()
+    }
+
     pub fn rows(&self) -> Iter<'_, GuessStr<N>> {
-        return self.guesses.iter();
+        self.guesses.iter()
     }
 
     pub fn guesses(&self) -> &[GuessStr<{ N }>] {
@@ -109,12 +238,29 @@ impl<const N: usize, const R: usize> GuessGrid<N, R> {
         &mut self.guesses[idx]
     }
 
-    /// Gets a list of all known chars, by position. This does not intelligently handle conflicting
-    /// information (e.g., one row saying char 0 is 'A' and another saying it's 'B').
+    /// Creates a grid of `row_count` rows, whose first rows are the given ones. Any remaining rows
+    /// are empty.
+    pub fn from_rows(rows: Vec<GuessStr<N>>, row_count: usize) -> Result<Self, String> {
+        if rows.len() > row_count {
+            return Err(format!(
+                "too many guesses: got {}, but only {} are allowed",
+                rows.len(),
+                row_count
+            ));
+        }
+        let mut grid = Self::new(row_count);
+        for (idx, row) in rows.into_iter().enumerate() {
+            grid.guesses[idx] = row;
+        }
+        Ok(grid)
+    }
+
+    /// Gets a list of all known chars, by position. If rows conflict (e.g., one row saying char 0 is
+    /// 'A' and another saying it's 'B'), the later row wins; use
+    /// `KnownWordConstraints::from_grid_checked` to find such conflicts.
     pub fn known_chars(&self) -> [Option<char>; N] {
         let mut result = [None; N];
 
-        // For now, don't c
         for row in self.rows() {
             if !row.is_fully_filled() {
                 break;
@@ -130,9 +276,9 @@ impl<const N: usize, const R: usize> GuessGrid<N, R> {
     }
 }
 
-impl<const N: usize, const R: usize> IntoIterator for GuessGrid<N, R> {
+impl<const N: usize> IntoIterator for GuessGrid<N> {
     type Item = GuessStr<N>;
-    type IntoIter = IntoIter<GuessStr<N>, R>;
+    type IntoIter = IntoIter<GuessStr<N>>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.guesses.into_iter()
@@ -142,3 +288,51 @@ impl<const N: usize, const R: usize> IntoIterator for GuessGrid<N, R> {
 fn init_array<T: Default, const N: usize>() -> [T; N] {
     [(); N].map(|_| T::default())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_guess_str() {
+        let parsed: GuessStr<5> = "crane:bYgBB".parse().unwrap();
+        assert_eq!("CRANE", parsed.word());
+        assert_eq!("BYGBB", parsed.feedback());
+    }
+
+    #[test]
+    fn parse_guess_str_emoji() {
+        // The black square here has a variation selector after it, as in Wordle's share text.
+        let parsed: GuessStr<5> = "CRANE:⬛️🟨🟩⬜🟧".parse().unwrap();
+        assert_eq!("BYGBG", parsed.feedback());
+    }
+
+    #[test]
+    fn parse_guess_str_errors() {
+        assert!("CRANE".parse::<GuessStr<5>>().is_err());
+        assert!("CRAN:BYGB".parse::<GuessStr<5>>().is_err());
+        assert!("CRANE:BYGB".parse::<GuessStr<5>>().is_err());
+        assert!("CRANE:BYGBX".parse::<GuessStr<5>>().is_err());
+        assert!("CR4NE:BYGBB".parse::<GuessStr<5>>().is_err());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut partial: GuessStr<5> = GuessStr::new();
+        partial.guess_mut(0).set_ch('C');
+        partial.guess_mut(0).set_knowledge(CharKnowledge::Correct);
+        partial.guess_mut(2).set_ch('A');
+        let json = serde_json::to_string(&partial).unwrap();
+        assert_eq!(r#"{"word":"C A  ","feedback":"G...."}"#, json);
+        let parsed: GuessStr<5> = serde_json::from_str(&json).unwrap();
+        assert_eq!("C A  ", parsed.word());
+        assert_eq!("G....", parsed.feedback());
+
+        assert!(
+            serde_json::from_str::<GuessStr<5>>(r#"{"word":"CRAN","feedback":"...."}"#).is_err()
+        );
+        assert!(
+            serde_json::from_str::<GuessStr<5>>(r#"{"word":"CR4NE","feedback":"....."}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::guess::guesses::GuessStr;
+use crate::guess::known_word_constraints::CharKnowledge;
+use crate::guess::pattern::Pattern;
+use crate::util::days_since_epoch;
+use crate::word_list::WordList;
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// The other side of a game: whoever knows the answer, and scores each guess against it.
+pub trait Host<const N: usize> {
+    /// Scores `guess`, returning the row as Wordle would show it. Fails if the guess isn't one
+    /// the host accepts.
+    fn score(&mut self, guess: &str) -> Result<GuessStr<N>, String>;
+
+    /// The answer; or, if the host hasn't had to commit to one yet, the one it would pick now.
+    fn answer(&self) -> &str;
+}
+
+/// A host with a fixed answer, like Wordle itself.
+pub struct FixedAnswer {
+    answer: String,
+}
+
+impl FixedAnswer {
+    pub fn new(answer: &str) -> Self {
+        FixedAnswer {
+            answer: answer.to_ascii_uppercase(),
+        }
+    }
+
+    /// Picks an answer from the `pool` most common words in `words`. The same seed always picks
+    /// the same answer from the same list; see also `daily_seed`.
+    pub fn random<const N: usize>(
+        words: &WordList<N>,
+        pool: usize,
+        seed: u64,
+    ) -> Result<Self, String> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        words
+            .words()
+            .take(pool)
+            .choose(&mut rng)
+            .map(|wf| FixedAnswer::new(&wf.word))
+            .ok_or_else(|| "there are no words to choose an answer from".to_string())
+    }
+}
+
+/// A seed that's the same all day (in UTC), and different the next day: the number of days since
+/// the Unix epoch. Seeding `FixedAnswer::random` with this gives everyone the same daily puzzle.
+pub fn daily_seed() -> u64 {
+    days_since_epoch()
+}
+
+impl<const N: usize> Host<N> for FixedAnswer {
+    fn score(&mut self, guess: &str) -> Result<GuessStr<N>, String> {
+        check_len::<N>(guess)?;
+        let mut row = GuessStr::new();
+        row.set_scored(guess, &self.answer);
+        Ok(row)
+    }
+
+    fn answer(&self) -> &str {
+        &self.answer
+    }
+}
+
+/// An adversarial host, as in Absurdle: it never picks an answer up front. Instead, after each
+/// guess, it sorts the words that are still possible into buckets by the feedback they'd give, and
+/// keeps whichever bucket is biggest. That makes every guess as unhelpful as it can be, so the
+/// number of guesses it takes to win is an analyzer's worst case.
+///
+/// When two buckets are the same size, it keeps the one with the fewest greens, and then the
+/// fewest yellows; so it only gives the win away once there's nothing else left.
+pub struct Absurdle {
+    /// The words that could still be the answer, most common first.
+    remaining: Vec<String>,
+}
+
+impl Absurdle {
+    pub fn new<const N: usize>(answers: &WordList<N>) -> Result<Self, String> {
+        if answers.is_empty() {
+            return Err("there are no words to choose an answer from".to_string());
+        }
+        Ok(Absurdle {
+            remaining: answers.words().map(|wf| wf.word.clone()).collect(),
+        })
+    }
+
+    /// The words that could still be the answer.
+    pub fn remaining(&self) -> &[String] {
+        &self.remaining
+    }
+}
+
+impl<const N: usize> Host<N> for Absurdle {
+    fn score(&mut self, guess: &str) -> Result<GuessStr<N>, String> {
+        check_len::<N>(guess)?;
+        // Keyed by pattern code, so that ties always go the same way.
+        let mut buckets: BTreeMap<usize, (Pattern<N>, Vec<String>)> = BTreeMap::new();
+        for word in self.remaining.drain(..) {
+            let pattern = Pattern::score(guess, &word);
+            buckets
+                .entry(pattern.code())
+                .or_insert_with(|| (pattern, Vec::new()))
+                .1
+                .push(word);
+        }
+        let count = |pattern: &Pattern<N>, knowledge: CharKnowledge| {
+            pattern
+                .knowledge()
+                .iter()
+                .filter(|k| **k == knowledge)
+                .count()
+        };
+        let (pattern, words) = buckets
+            .into_values()
+            .min_by_key(|(pattern, words)| {
+                (
+                    Reverse(words.len()),
+                    count(pattern, CharKnowledge::Correct),
+                    count(pattern, CharKnowledge::WrongPosition),
+                )
+            })
+            .expect("there's always at least one word left");
+        self.remaining = words;
+        let mut row = GuessStr::new();
+        row.set_scored(guess, &self.remaining[0]);
+        debug_assert_eq!(pattern, row.pattern());
+        Ok(row)
+    }
+
+    fn answer(&self) -> &str {
+        &self.remaining[0]
+    }
+}
+
+fn check_len<const N: usize>(guess: &str) -> Result<(), String> {
+    if guess.chars().count() != N {
+        return Err(format!("{} isn't a {}-letter word", guess, N));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed_answer() {
+        let mut host = FixedAnswer::new("stair");
+        let row: GuessStr<5> = host.score("CRANE").unwrap();
+        assert_eq!("BYGBB", row.feedback());
+        assert!(Host::<5>::score(&mut host, "CRANES").is_err());
+    }
+
+    #[test]
+    fn random_answers() {
+        let words: WordList<5> = WordList::std_with_limit(500).unwrap();
+        let pick = |seed| {
+            let host = FixedAnswer::random(&words, 100, seed).unwrap();
+            Host::<5>::answer(&host).to_string()
+        };
+        assert_eq!(pick(1), pick(1));
+        let top_words: Vec<&str> = words.words().take(100).map(|wf| wf.word.as_str()).collect();
+        let answers: Vec<String> = (0..20).map(pick).collect();
+        assert!(answers.iter().all(|a| top_words.contains(&a.as_str())));
+        assert!(answers.iter().any(|a| *a != answers[0]));
+
+        assert!(FixedAnswer::random(&WordList::<5>::empty(), 100, 1).is_err());
+    }
+
+    #[test]
+    fn absurdle_keeps_the_biggest_bucket() {
+        let words: WordList<5> =
+            WordList::from_words(&["STAIR", "SHIRT", "CRANE", "TRAIN", "FLOUT"]);
+        let mut host = Absurdle::new(&words).unwrap();
+        // STAIR and TRAIN both have FLOUT's T, but not where FLOUT has it; that's the biggest
+        // bucket.
+        let row: GuessStr<5> = host.score("FLOUT").unwrap();
+        assert_eq!("BBBBY", row.feedback());
+        assert_eq!(vec!["STAIR", "TRAIN"], host.remaining());
+
+        // Each of those is in a bucket of its own now, so the one with no greens wins.
+        let row: GuessStr<5> = host.score("SHIRT").unwrap();
+        assert_eq!("BBYYY", row.feedback());
+        assert_eq!(vec!["TRAIN"], host.remaining());
+        assert_eq!("TRAIN", Host::<5>::answer(&host));
+
+        // With one word left, guessing it wins.
+        let row: GuessStr<5> = host.score("TRAIN").unwrap();
+        assert_eq!("GGGGG", row.feedback());
+    }
+}
@@ -1,19 +1,53 @@
+use crate::guess::compiled_constraints::CompiledConstraints;
 use crate::guess::guesses::{GuessGrid, GuessStr};
 use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 use strum::{EnumCount, FromRepr};
 
-#[derive(Copy, Clone, PartialEq, Eq, EnumCount, FromRepr)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, EnumCount, FromRepr, Default)]
 pub enum CharKnowledge {
+    #[default]
     Unknown,
     WrongPosition,
     Correct,
     Missing,
 }
 
-impl Default for CharKnowledge {
-    fn default() -> Self {
-        CharKnowledge::Unknown
+impl CharKnowledge {
+    /// A one-char representation of this knowledge, for plain-text feedback strings: `G` (green)
+    /// for correct, `Y` (yellow) for the wrong position, `B` (black) for missing and `.` for
+    /// unknown.
+    pub fn feedback_char(&self) -> char {
+        match self {
+            CharKnowledge::Unknown => '.',
+            CharKnowledge::WrongPosition => 'Y',
+            CharKnowledge::Correct => 'G',
+            CharKnowledge::Missing => 'B',
+        }
+    }
+
+    /// The emoji square for this knowledge, as in Wordle's share text.
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            CharKnowledge::Unknown => "⤵️",
+            CharKnowledge::WrongPosition => "🟨",
+            CharKnowledge::Correct => "🟩",
+            CharKnowledge::Missing => "⬛️",
+        }
+    }
+
+    /// The inverse of `feedback_char`, case-insensitively. This also accepts the emoji squares from
+    /// Wordle's share text, in both the standard and high-contrast color schemes.
+    ///
+    /// `Unknown` is never returned; feedback is always known.
+    pub fn from_feedback_char(ch: char) -> Option<Self> {
+        match ch {
+            'G' | 'g' | '🟩' | '🟧' => Some(CharKnowledge::Correct),
+            'Y' | 'y' | '🟨' | '🟦' => Some(CharKnowledge::WrongPosition),
+            'B' | 'b' | '⬛' | '⬜' => Some(CharKnowledge::Missing),
+            _ => None,
+        }
     }
 }
 
@@ -29,6 +63,9 @@ impl<const N: usize> KnownWordConstraints<N> {
     pub fn is_word_possible(&self, word: &str) -> bool {
         // First, check all the positional info.
         for (idx, word_ch) in word.chars().enumerate() {
+            if self.missing.contains(&word_ch) {
+                return false;
+            }
             // If we know this idx has to contain known_ch, but it doesn't, then return false.
             if let Some(known_ch) = self.fully_known[idx] {
                 if word_ch != known_ch {
@@ -56,7 +93,32 @@ impl<const N: usize> KnownWordConstraints<N> {
                 }
             }
         }
-        return true;
+        true
+    }
+
+    /// Compiles these constraints into a form that's much quicker to check words against, at the
+    /// cost of the words needing to be encoded first. `WordList` does that encoding up front.
+    pub fn compile(&self) -> CompiledConstraints<N> {
+        let mut compiled = CompiledConstraints::allow_all();
+        for idx in 0..N {
+            if let Some(ch) = self.fully_known[idx] {
+                compiled.require(idx, ch);
+            }
+            for ch in &self.wrong_positions[idx] {
+                compiled.forbid(idx, *ch);
+            }
+            for ch in &self.missing {
+                compiled.forbid(idx, *ch);
+            }
+        }
+        for (ch, known) in &self.letters_count.0 {
+            compiled.limit_count(
+                *ch,
+                known.at_least as u32,
+                known.no_more_than.map(|n| n as u32),
+            );
+        }
+        compiled
     }
 
     pub fn empty() -> Self {
@@ -68,19 +130,135 @@ impl<const N: usize> KnownWordConstraints<N> {
         }
     }
 
-    pub fn from_grid<const R: usize>(grid: &GuessGrid<N, R>) -> Self {
+    /// The constraints that hard mode places on guesses: any revealed hints must be used in
+    /// subsequent guesses.
+    ///
+    /// This is looser than the constraints themselves. A guess must keep every correct letter where
+    /// it is, and must include every letter we know is in the word (at least as many times as we
+    /// know of). But unlike a possible answer, it may reuse letters we know are missing, or put a
+    /// letter back in a position we know is wrong for it.
+    pub fn hard_mode(&self) -> Self {
+        let letters_count = self
+            .letters_count
+            .0
+            .iter()
+            .filter(|(_, known)| known.at_least > 0)
+            .map(|(ch, known)| {
+                let hard_mode_known = LetterKnowledge {
+                    at_least: known.at_least,
+                    no_more_than: None,
+                };
+                (*ch, hard_mode_known)
+            })
+            .collect();
+        KnownWordConstraints {
+            fully_known: self.fully_known,
+            letters_count: KnowledgePerLetter(letters_count),
+            ..Self::empty()
+        }
+    }
+
+    pub fn from_grid(grid: &GuessGrid<N>) -> Self {
         let mut result = Self::empty();
+        grid.rows().for_each(|r| result.add_row(r));
+        result
+    }
 
-        for row in grid.rows() {
-            result.add_row(row);
-            let row_counts = KnowledgePerLetter::from(row);
-            result.letters_count.add(&row_counts);
+    /// Like `from_grid`, but first checks that the rows agree with each other. If they don't, no
+    /// word could possibly match them all (or worse, `from_grid` would quietly drop some of the
+    /// conflicting knowledge); so instead, this returns everything that's wrong.
+    pub fn from_grid_checked(grid: &GuessGrid<N>) -> Result<Self, Vec<Contradiction>> {
+        let contradictions = Self::contradictions(grid);
+        if contradictions.is_empty() {
+            Ok(Self::from_grid(grid))
+        } else {
+            Err(contradictions)
+        }
+    }
+
+    fn contradictions(grid: &GuessGrid<N>) -> Vec<Contradiction> {
+        let rows = grid.guesses();
+        let mut result = Vec::new();
+
+        // A green letter conflicts with a different green letter in the same column, and with the
+        // same letter being excluded from that column.
+        for col in 0..N {
+            let mut first_correct: Option<(usize, char)> = None;
+            for (row_idx, row) in rows.iter().enumerate() {
+                let cell = &row.guesses()[col];
+                let (Some(ch), CharKnowledge::Correct) = (cell.ch(), cell.knowledge()) else {
+                    continue;
+                };
+                match first_correct {
+                    None => first_correct = Some((row_idx, ch)),
+                    Some((first_row, first_ch)) if first_ch != ch => result.push(Contradiction {
+                        kind: ContradictionKind::DifferentCorrectLetters(first_ch, ch),
+                        cells: vec![GridCell::new(first_row, col), GridCell::new(row_idx, col)],
+                    }),
+                    Some(_) => {}
+                }
+                for (other_idx, other) in rows.iter().enumerate() {
+                    if excluded_letter(other, col) == Some(ch) {
+                        result.push(Contradiction {
+                            kind: ContradictionKind::CorrectButExcluded(ch),
+                            cells: vec![GridCell::new(row_idx, col), GridCell::new(other_idx, col)],
+                        })
+                    }
+                }
+            }
         }
-        grid.rows().for_each(|r| result.add_row(r));
 
-        return result;
+        // A row that shows at least n copies of a letter conflicts with one that caps it below n.
+        let per_row: Vec<KnowledgePerLetter> = rows.iter().map(KnowledgePerLetter::from).collect();
+        let letters: BTreeSet<char> = per_row.iter().flat_map(|k| k.0.keys().copied()).collect();
+        for ch in letters {
+            let known = |row_idx: &usize| per_row[*row_idx].0.get(&ch);
+            let most = (0..rows.len())
+                .filter_map(|r| known(&r).map(|k| (r, k.at_least)))
+                .rev() // so that max_by_key picks the earliest row, on ties
+                .max_by_key(|(_, at_least)| *at_least);
+            let fewest = (0..rows.len())
+                .filter_map(|r| known(&r).and_then(|k| k.no_more_than).map(|n| (r, n)))
+                .min_by_key(|(_, no_more_than)| *no_more_than);
+            let (Some((at_least_row, at_least)), Some((no_more_than_row, no_more_than))) =
+                (most, fewest)
+            else {
+                continue;
+            };
+            if at_least > no_more_than {
+                let cells_where = |row_idx: usize, present: bool| {
+                    rows[row_idx]
+                        .chars()
+                        .enumerate()
+                        .filter(move |(_, cell)| {
+                            cell.ch() == Some(ch)
+                                && (cell.knowledge() == CharKnowledge::Missing) != present
+                        })
+                        .map(move |(col, _)| GridCell::new(row_idx, col))
+                };
+                result.push(Contradiction {
+                    kind: ContradictionKind::LetterCount {
+                        ch,
+                        at_least,
+                        no_more_than,
+                    },
+                    cells: cells_where(at_least_row, true)
+                        .chain(cells_where(no_more_than_row, false))
+                        .collect(),
+                })
+            }
+        }
+        result
     }
 
+    /// Adds the knowledge from one row, modelled on how Wordle scores a guess.
+    ///
+    /// Wordle marks a guessed letter as missing whenever the answer has no more of that letter
+    /// left over, once the greens and (earlier) yellows have claimed theirs. So a missing letter
+    /// only means the letter isn't in the word at all if no other copy of it in the row was green
+    /// or yellow. Otherwise, it tells us two things: the letter isn't at this position (or it would
+    /// have been green), and the word has exactly as many of the letter as the row's greens and
+    /// yellows account for. `KnowledgePerLetter` handles the latter.
     pub fn add_row(&mut self, str: &GuessStr<N>) {
         for (idx, guess_ch) in str.chars().enumerate() {
             let Some(ch) = guess_ch.ch() else {
@@ -92,11 +270,217 @@ impl<const N: usize> KnownWordConstraints<N> {
                     self.wrong_positions.get_mut(idx).unwrap().insert(ch);
                 }
                 CharKnowledge::Missing => {
-                    self.missing.insert(ch);
+                    if excluded_letter(str, idx).is_some() {
+                        self.wrong_positions.get_mut(idx).unwrap().insert(ch);
+                    } else {
+                        self.missing.insert(ch);
+                    }
                 }
                 CharKnowledge::Unknown => continue,
             };
         }
+        self.letters_count.add(&KnowledgePerLetter::from(str));
+    }
+
+    /// Explains why `word` isn't possible, given the grid: returns the first constraint it violates,
+    /// along with the cell that the constraint came from. Returns `None` if the word is still
+    /// possible.
+    ///
+    /// "First" is in row order; within a row, the positional constraints come before the letter
+    /// counts.
+    pub fn explain(grid: &GuessGrid<N>, word: &str) -> Option<Violation> {
+        let word: Vec<char> = word.chars().map(|c| c.to_ascii_uppercase()).collect();
+        for (row_idx, row) in grid.rows().enumerate() {
+            let violation = |col: usize, kind: ViolationKind| {
+                Some(Violation {
+                    cell: GridCell::new(row_idx, col),
+                    kind,
+                })
+            };
+            for (col, cell) in row.chars().enumerate() {
+                let Some(ch) = cell.ch() else {
+                    continue;
+                };
+                let word_ch = word.get(col).copied();
+                match cell.knowledge() {
+                    CharKnowledge::Correct if word_ch != Some(ch) => {
+                        return violation(col, ViolationKind::WrongLetter(ch));
+                    }
+                    CharKnowledge::WrongPosition | CharKnowledge::Missing
+                        if word_ch == Some(ch) && excluded_letter(row, col).is_some() =>
+                    {
+                        return violation(col, ViolationKind::BannedPosition(ch));
+                    }
+                    _ => {}
+                }
+            }
+            let letters_count = KnowledgePerLetter::from(row);
+            let mut letters: Vec<&char> = letters_count.0.keys().collect();
+            letters.sort();
+            for ch in letters {
+                let known = &letters_count.0[ch];
+                let in_word = word.iter().filter(|c| *c == ch).count();
+                // Point at a cell that shows the letter is there, or one that shows it isn't.
+                let first_cell = |present: bool| {
+                    row.chars()
+                        .position(|cell| {
+                            cell.ch() == Some(*ch)
+                                && (cell.knowledge() == CharKnowledge::Missing) != present
+                        })
+                        .unwrap_or(0)
+                };
+                if in_word < known.at_least {
+                    let kind = ViolationKind::TooFew {
+                        ch: *ch,
+                        at_least: known.at_least,
+                    };
+                    return violation(first_cell(true), kind);
+                }
+                if let Some(no_more_than) = known.no_more_than {
+                    if in_word > no_more_than {
+                        let kind = ViolationKind::TooMany {
+                            ch: *ch,
+                            no_more_than,
+                        };
+                        return violation(first_cell(false), kind);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// If this row tells us that its letter at `col` isn't at that position, but without ruling the
+/// letter out of the word entirely, returns that letter. That's the case for a yellow letter, and
+/// for a grey one when another copy of it in the row is green, yellow or not yet known.
+fn excluded_letter<const N: usize>(row: &GuessStr<N>, col: usize) -> Option<char> {
+    let cell = &row.guesses()[col];
+    let ch = cell.ch()?;
+    match cell.knowledge() {
+        CharKnowledge::WrongPosition => Some(ch),
+        CharKnowledge::Missing => row
+            .chars()
+            .any(|other| other.ch() == Some(ch) && other.knowledge() != CharKnowledge::Missing)
+            .then_some(ch),
+        CharKnowledge::Correct | CharKnowledge::Unknown => None,
+    }
+}
+
+/// A single cell in a `GuessGrid`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct GridCell {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl GridCell {
+    fn new(row: usize, col: usize) -> Self {
+        GridCell { row, col }
+    }
+}
+
+/// Two or more cells in a grid whose feedback can't all be true at once.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Contradiction {
+    pub kind: ContradictionKind,
+    /// The cells involved. The first cell's row is the one whose knowledge is described first in
+    /// the `ContradictionKind` (for example, the one with `at_least` copies of a letter), and the
+    /// last cell's row is the other one.
+    pub cells: Vec<GridCell>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ContradictionKind {
+    /// Two rows have different green letters in the same column.
+    DifferentCorrectLetters(char, char),
+    /// One row has this letter green in a column, but another says it isn't in that column.
+    CorrectButExcluded(char),
+    /// One row shows at least `at_least` copies of a letter, but another shows that there are no
+    /// more than `no_more_than`.
+    LetterCount {
+        ch: char,
+        at_least: usize,
+        no_more_than: usize,
+    },
+}
+
+impl Display for Contradiction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Rows and columns are 1-based here, since this is for people to read.
+        let first = self.cells.first().map(|c| c.row + 1).unwrap_or(0);
+        let last = self.cells.last().map(|c| c.row + 1).unwrap_or(0);
+        let col = self.cells.first().map(|c| c.col + 1).unwrap_or(0);
+        match self.kind {
+            ContradictionKind::DifferentCorrectLetters(a, b) => write!(
+                f,
+                "rows {} and {} have different correct letters in column {} ({} and {})",
+                first, last, col, a, b
+            ),
+            ContradictionKind::CorrectButExcluded(ch) => write!(
+                f,
+                "row {} has {} correct in column {}, but row {} says it isn't there",
+                first, ch, col, last
+            ),
+            ContradictionKind::LetterCount {
+                ch,
+                at_least,
+                no_more_than,
+            } => write!(
+                f,
+                "row {} has at least {} {}, but row {} has no more than {}",
+                first, at_least, ch, last, no_more_than
+            ),
+        }
+    }
+}
+
+/// The first constraint that a word violates; see `KnownWordConstraints::explain`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Violation {
+    /// The cell whose feedback rules the word out.
+    pub cell: GridCell,
+    pub kind: ViolationKind,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ViolationKind {
+    /// The cell has this letter in green, but the word has something else there.
+    WrongLetter(char),
+    /// The cell says this letter isn't at its position, but the word has it there.
+    BannedPosition(char),
+    /// The cell's row shows that there are at least this many of the letter, but the word has
+    /// fewer.
+    TooFew { ch: char, at_least: usize },
+    /// The cell's row shows that there are no more than this many of the letter, but the word has
+    /// more.
+    TooMany { ch: char, no_more_than: usize },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let row = self.cell.row + 1;
+        let col = self.cell.col + 1;
+        match self.kind {
+            ViolationKind::WrongLetter(ch) => {
+                write!(f, "row {} has {} in column {}", row, ch, col)
+            }
+            ViolationKind::BannedPosition(ch) => {
+                write!(f, "row {} says {} isn't in column {}", row, ch, col)
+            }
+            ViolationKind::TooFew { ch, at_least } => {
+                write!(f, "row {} has at least {} {}", row, at_least, ch)
+            }
+            ViolationKind::TooMany {
+                ch,
+                no_more_than: 0,
+            } => {
+                write!(f, "row {} has no {}", row, ch)
+            }
+            ViolationKind::TooMany { ch, no_more_than } => {
+                write!(f, "row {} has no more than {} {}", row, no_more_than, ch)
+            }
+        }
     }
 }
 
@@ -131,7 +515,7 @@ impl KnowledgePerLetter {
             match guess.knowledge() {
                 CharKnowledge::WrongPosition | CharKnowledge::Correct => {
                     ch = ch.to_ascii_uppercase();
-                    let count = result.0.entry(ch).or_insert(Default::default());
+                    let count = result.0.entry(ch).or_default();
                     count.at_least += 1;
                 }
                 CharKnowledge::Missing => {} // will be handled below
@@ -144,17 +528,25 @@ impl KnowledgePerLetter {
             };
             if guess.knowledge() == CharKnowledge::Missing {
                 ch = ch.to_ascii_uppercase();
-                let count = result.0.entry(ch).or_insert(Default::default());
-                count.no_more_than = Some(count.at_least);
+                // If another copy of this letter doesn't have its knowledge yet, we can't know the
+                // count: that copy might still turn out to be green or yellow.
+                let any_unknown = string.chars().any(|other| {
+                    other.ch().map(|c| c.to_ascii_uppercase()) == Some(ch)
+                        && other.knowledge() == CharKnowledge::Unknown
+                });
+                let count = result.0.entry(ch).or_default();
+                if !any_unknown {
+                    count.no_more_than = Some(count.at_least);
+                }
             }
         }
-        return result;
+        result
     }
 
     fn add(&mut self, other: &KnowledgePerLetter) {
         for (ch, other_count) in &other.0 {
             let entry = self.0.entry(*ch);
-            let my_count = entry.or_insert_with(|| LetterKnowledge::default());
+            let my_count = entry.or_default();
             let at_least = max(my_count.at_least, other_count.at_least);
             let no_more_than = match (my_count.no_more_than, other_count.no_more_than) {
                 (Some(my_ceil), Some(other_ceil)) => Some(min(my_ceil, other_ceil)),
@@ -164,7 +556,8 @@ impl KnowledgePerLetter {
             };
             if let Some(ceil) = no_more_than {
                 if at_least > ceil {
-                    // We got conflicting data! Just ignore this letter
+                    // We got conflicting data! Just ignore this letter; from_grid_checked is how
+                    // callers find out about it.
                     continue;
                 }
             }
@@ -178,6 +571,10 @@ impl KnowledgePerLetter {
 mod test {
     use super::*;
     use crate::guess::guesses::{GuessGrid, GuessStr};
+    use crate::word_list::WordList;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
     use std::collections::HashMap;
 
     #[test]
@@ -238,7 +635,7 @@ mod test {
 
     #[test]
     fn create_known_word_constraints() {
-        let mut grid: GuessGrid<5, 6> = GuessGrid::new();
+        let mut grid: GuessGrid<5> = GuessGrid::new(6);
         write_chars(
             grid.guess_mut(0),
             [
@@ -304,6 +701,291 @@ mod test {
         assert!(actual.is_word_possible("QUALM"))
     }
 
+    #[test]
+    fn hard_mode_constraints() {
+        let mut grid: GuessGrid<5> = GuessGrid::new(6);
+        write_chars(
+            grid.guess_mut(0),
+            [
+                ('C', CharKnowledge::Missing),
+                ('L', CharKnowledge::WrongPosition),
+                ('A', CharKnowledge::Correct),
+                ('S', CharKnowledge::Missing),
+                ('H', CharKnowledge::Missing),
+            ],
+        );
+        let constraints = KnownWordConstraints::from_grid(&grid);
+        let hard_mode = constraints.hard_mode();
+
+        // A possible answer is always a valid hard-mode guess.
+        assert!(constraints.is_word_possible("QUALM"));
+        assert!(hard_mode.is_word_possible("QUALM"));
+
+        // Reusing a missing letter, or an 'L' in the same wrong position, is allowed in hard mode.
+        assert!(!constraints.is_word_possible("CLASP"));
+        assert!(hard_mode.is_word_possible("CLASP"));
+
+        // But we have to keep the 'A' where it is, and we have to use the 'L'.
+        assert!(!hard_mode.is_word_possible("ALOFT"));
+        assert!(!hard_mode.is_word_possible("PEACH"));
+    }
+
+    #[test]
+    fn duplicate_letters() {
+        // (guess, answer, a word that's still possible, a word that isn't)
+        let cases = [
+            // One E is green and the other is grey: exactly one E, and not at index 1.
+            ("EERIE", "THEME", "THEME", "THERE"),
+            // The second S is grey, but the first is yellow: exactly one S, not at index 0 or 2.
+            ("SASSY", "ROAST", "ROAST", "ASSET"),
+            // Both Ls are yellow: at least two Ls.
+            ("LLAMA", "SKULL", "SKULL", "LEAPT"),
+            // A letter that's only ever grey isn't in the word at all.
+            ("SPEED", "ABIDE", "ABIDE", "PRIDE"),
+        ];
+        for (guess, answer, possible, impossible) in cases {
+            let mut grid: GuessGrid<5> = GuessGrid::new(6);
+            grid.guess_mut(0).set_scored(guess, answer);
+            let constraints = KnownWordConstraints::from_grid(&grid);
+            assert!(
+                constraints.is_word_possible(possible),
+                "{} should be possible after {}",
+                possible,
+                guess
+            );
+            assert!(
+                !constraints.is_word_possible(impossible),
+                "{} shouldn't be possible after {}",
+                impossible,
+                guess
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_letters_dont_cap_counts() {
+        // The second S doesn't have its feedback yet, so it might still be yellow.
+        let mut grid: GuessGrid<5> = GuessGrid::new(6);
+        write_chars(
+            grid.guess_mut(0),
+            [
+                ('S', CharKnowledge::Missing),
+                ('A', CharKnowledge::Missing),
+                ('S', CharKnowledge::Unknown),
+                ('S', CharKnowledge::Unknown),
+                ('Y', CharKnowledge::Missing),
+            ],
+        );
+        let constraints = KnownWordConstraints::from_grid(&grid);
+        assert!(constraints.is_word_possible("ROUST"));
+        assert!(!constraints.is_word_possible("SPORT"));
+    }
+
+    /// For random answers and guesses, a word passes `is_word_possible` exactly when it would have
+    /// gotten the same feedback as the answer did, for every guess. In particular, the answer itself
+    /// always passes.
+    #[test]
+    fn constraints_match_wordle_scoring() {
+        let words: Vec<String> = WordList::<5>::std_with_limit(1000)
+            .unwrap()
+            .words()
+            .map(|wf| wf.word.clone())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(0x5EED);
+        for _ in 0..300 {
+            let answer = words.choose(&mut rng).unwrap();
+            let guesses: Vec<&String> = words.choose_multiple(&mut rng, 3).collect();
+            let mut grid: GuessGrid<5> = GuessGrid::new(6);
+            for (idx, guess) in guesses.iter().enumerate() {
+                grid.guess_mut(idx).set_scored(guess, answer);
+            }
+            let constraints = KnownWordConstraints::from_grid(&grid);
+            assert!(
+                constraints.is_word_possible(answer),
+                "{} was ruled out by its own feedback for {:?}",
+                answer,
+                guesses
+            );
+            for candidate in words.choose_multiple(&mut rng, 50) {
+                let same_feedback = guesses.iter().enumerate().all(|(idx, guess)| {
+                    let mut scored: GuessStr<5> = GuessStr::new();
+                    scored.set_scored(guess, candidate);
+                    scored.feedback() == grid.guesses()[idx].feedback()
+                });
+                assert_eq!(
+                    same_feedback,
+                    constraints.is_word_possible(candidate),
+                    "answer {}, guesses {:?}, candidate {}",
+                    answer,
+                    guesses,
+                    candidate
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn contradictions() {
+        let grid = |rows: &[&str]| -> GuessGrid<5> {
+            GuessGrid::from_rows(rows.iter().map(|r| r.parse().unwrap()).collect(), 6).unwrap()
+        };
+
+        // Consistent feedback has no contradictions.
+        let ok = grid(&["CRANE:BYGBB", "TARES:YYYBY"]);
+        assert!(KnownWordConstraints::from_grid_checked(&ok).is_ok());
+
+        let different_correct = grid(&["CRANE:GBBBB", "SHALT:GBBBB"]);
+        assert_eq!(
+            vec![Contradiction {
+                kind: ContradictionKind::DifferentCorrectLetters('C', 'S'),
+                cells: vec![GridCell::new(0, 0), GridCell::new(1, 0)],
+            }],
+            KnownWordConstraints::from_grid_checked(&different_correct).unwrap_err()
+        );
+
+        let excluded = grid(&["CRANE:BBGBB", "PLAID:BBYBB"]);
+        assert_eq!(
+            vec![Contradiction {
+                kind: ContradictionKind::CorrectButExcluded('A'),
+                cells: vec![GridCell::new(0, 2), GridCell::new(1, 2)],
+            }],
+            KnownWordConstraints::from_grid_checked(&excluded).unwrap_err()
+        );
+
+        // The first row says there's no E at all; the second says there are two.
+        let count = grid(&["CRANE:BBBBB", "EERIE:GYBBB"]);
+        let err = KnownWordConstraints::from_grid_checked(&count).unwrap_err();
+        assert_eq!(
+            vec![Contradiction {
+                kind: ContradictionKind::LetterCount {
+                    ch: 'E',
+                    at_least: 2,
+                    no_more_than: 0,
+                },
+                cells: vec![
+                    GridCell::new(1, 0),
+                    GridCell::new(1, 1),
+                    GridCell::new(0, 4),
+                ],
+            }],
+            err
+        );
+        assert_eq!(
+            "row 2 has at least 2 E, but row 1 has no more than 0",
+            err[0].to_string()
+        );
+    }
+
+    /// A grey and a yellow of the same letter in one row mean the same thing whichever comes first:
+    /// exactly one of the letter, in neither of those columns.
+    #[test]
+    fn grey_and_yellow_in_either_order() {
+        let grid = |rows: &[&str]| -> GuessGrid<5> {
+            GuessGrid::from_rows(rows.iter().map(|r| r.parse().unwrap()).collect(), 6).unwrap()
+        };
+        // SPEED's Es are at indexes 2 and 3; `grey_col` is the grey one's.
+        for (row, grey_col) in [("SPEED:BBYBB", 3), ("SPEED:BBBYB", 2)] {
+            assert!(KnownWordConstraints::from_grid_checked(&grid(&[row])).is_ok());
+
+            let two_es = grid(&[row, "EERIE:YYBBB"]);
+            assert_eq!(
+                vec![Contradiction {
+                    kind: ContradictionKind::LetterCount {
+                        ch: 'E',
+                        at_least: 2,
+                        no_more_than: 1,
+                    },
+                    cells: vec![
+                        GridCell::new(1, 0),
+                        GridCell::new(1, 1),
+                        GridCell::new(0, grey_col),
+                    ],
+                }],
+                KnownWordConstraints::from_grid_checked(&two_es).unwrap_err(),
+                "{}",
+                row
+            );
+
+            // A green E where the grey one was.
+            let green = if grey_col == 3 {
+                "ASKED:BBBGB"
+            } else {
+                "OCEAN:BBGBB"
+            };
+            assert_eq!(
+                vec![Contradiction {
+                    kind: ContradictionKind::CorrectButExcluded('E'),
+                    cells: vec![GridCell::new(1, grey_col), GridCell::new(0, grey_col)],
+                }],
+                KnownWordConstraints::from_grid_checked(&grid(&[row, green])).unwrap_err(),
+                "{}",
+                row
+            );
+        }
+    }
+
+    #[test]
+    fn explain() {
+        let grid: GuessGrid<5> = GuessGrid::from_rows(
+            vec![
+                "CRANE:BYGBB".parse().unwrap(),
+                "TARES:YYYBY".parse().unwrap(),
+            ],
+            6,
+        )
+        .unwrap();
+        let explain = |word| KnownWordConstraints::explain(&grid, word).map(|v| v.to_string());
+
+        assert_eq!(None, explain("STAIR"));
+        assert_eq!(
+            Some("row 1 has A in column 3".to_string()),
+            explain("SHIRT")
+        );
+        assert_eq!(
+            Some("row 1 says R isn't in column 2".to_string()),
+            explain("ARAIS")
+        );
+        assert_eq!(Some("row 1 has no C".to_string()), explain("SHACK"));
+        assert_eq!(Some("row 2 has at least 1 S".to_string()), explain("OTARY"));
+        assert_eq!(
+            Some(Violation {
+                cell: GridCell::new(1, 0),
+                kind: ViolationKind::TooFew {
+                    ch: 'T',
+                    at_least: 1
+                },
+            }),
+            KnownWordConstraints::explain(&grid, "SHARD")
+        );
+    }
+
+    /// `explain` agrees with `is_word_possible` about which words are possible.
+    #[test]
+    fn explain_matches_is_word_possible() {
+        let words: Vec<String> = WordList::<5>::std_with_limit(1000)
+            .unwrap()
+            .words()
+            .map(|wf| wf.word.clone())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(0xE4A1);
+        for _ in 0..100 {
+            let answer = words.choose(&mut rng).unwrap();
+            let mut grid: GuessGrid<5> = GuessGrid::new(6);
+            for (idx, guess) in words.choose_multiple(&mut rng, 2).enumerate() {
+                grid.guess_mut(idx).set_scored(guess, answer);
+            }
+            let constraints = KnownWordConstraints::from_grid(&grid);
+            for word in words.choose_multiple(&mut rng, 50) {
+                assert_eq!(
+                    constraints.is_word_possible(word),
+                    KnownWordConstraints::explain(&grid, word).is_none(),
+                    "{}",
+                    word
+                );
+            }
+        }
+    }
+
     fn sorted_vec<I, T>(iterable: I) -> Vec<T>
     where
         I: IntoIterator<Item = T>,
@@ -1,2 +1,7 @@
+pub mod compiled_constraints;
 pub mod guesses;
+pub mod host;
 pub mod known_word_constraints;
+pub mod multi_board;
+pub mod pattern;
+pub mod share;
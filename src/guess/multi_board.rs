@@ -0,0 +1,208 @@
+use crate::guess::guesses::GuessGrid;
+use crate::guess::known_word_constraints::{CharKnowledge, Contradiction, KnownWordConstraints};
+use crate::word_list::WordList;
+
+/// A game played on several boards at once, like Dordle (2 boards), Quordle (4) or Octordle (8).
+/// Each guess is made on every board, but each board has its own answer, and so its own feedback.
+/// Once a board is solved, it stops taking guesses.
+pub struct MultiBoard<const N: usize> {
+    boards: Vec<GuessGrid<N>>,
+    /// How many guesses the game allows.
+    row_count: usize,
+    /// How many guesses have been made so far: that is, how many rows are filled in on every
+    /// unsolved board.
+    guess_count: usize,
+}
+
+/// One board's state, for analysis.
+pub enum BoardState<const N: usize> {
+    /// The board was solved in the given number of guesses.
+    Solved(usize),
+    /// The board's answer is one of these words.
+    Open(WordList<N>),
+    /// The board's feedback contradicts itself.
+    Contradiction(Vec<Contradiction>),
+}
+
+impl<const N: usize> MultiBoard<N> {
+    pub fn new(board_count: usize, row_count: usize) -> Self {
+        MultiBoard {
+            boards: (0..board_count)
+                .map(|_| GuessGrid::new(row_count))
+                .collect(),
+            row_count,
+            guess_count: 0,
+        }
+    }
+
+    pub fn boards(&self) -> &[GuessGrid<N>] {
+        &self.boards
+    }
+
+    pub fn board_mut(&mut self, board: usize) -> &mut GuessGrid<N> {
+        &mut self.boards[board]
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn guess_count(&self) -> usize {
+        self.guess_count
+    }
+
+    /// If `board` has been solved, the number of guesses it took.
+    pub fn solved_in(&self, board: usize) -> Option<usize> {
+        self.boards[board]
+            .rows()
+            .take(self.guess_count)
+            .position(|row| row.pattern().is_solved())
+            .map(|idx| idx + 1)
+    }
+
+    pub fn is_solved(&self, board: usize) -> bool {
+        self.solved_in(board).is_some()
+    }
+
+    pub fn all_solved(&self) -> bool {
+        (0..self.boards.len()).all(|board| self.is_solved(board))
+    }
+
+    /// Whether the game is over: either every board is solved, or there are no rows left.
+    pub fn is_over(&self) -> bool {
+        self.all_solved() || self.guess_count >= self.row_count
+    }
+
+    /// Guesses `guess` on every unsolved board, with the feedback it would get if each board's
+    /// answer were the corresponding one in `answers`.
+    pub fn add_scored(&mut self, guess: &str, answers: &[&str]) -> Result<(), String> {
+        if answers.len() != self.boards.len() {
+            return Err(format!(
+                "expected {} answers (one per board), but got {}",
+                self.boards.len(),
+                answers.len()
+            ));
+        }
+        if self.is_over() {
+            return Err("the game is already over".to_string());
+        }
+        for board in self.unsolved_boards() {
+            self.boards[board]
+                .guess_mut(self.guess_count)
+                .set_scored(guess, answers[board]);
+        }
+        self.advance()
+    }
+
+    /// Marks the current row as complete on every unsolved board, and moves on to the next. Every
+    /// unsolved board must have its current row fully filled in, with the same word, and feedback
+    /// that doesn't contradict its earlier rows.
+    pub fn advance(&mut self) -> Result<(), String> {
+        if self.is_over() {
+            return Err("the game is already over".to_string());
+        }
+        let mut word = None;
+        for board in self.unsolved_boards() {
+            let row = &self.boards[board].guesses()[self.guess_count];
+            if row
+                .chars()
+                .any(|ch| ch.ch().is_none() || ch.knowledge() == CharKnowledge::Unknown)
+            {
+                return Err(format!("board {}'s guess isn't filled in yet", board + 1));
+            }
+            match &word {
+                None => word = Some(row.word()),
+                Some(word) if *word != row.word() => {
+                    return Err(format!(
+                        "board {} has {}, but the other boards have {}",
+                        board + 1,
+                        row.word(),
+                        word
+                    ))
+                }
+                Some(_) => {}
+            }
+            if KnownWordConstraints::from_grid_checked(&self.boards[board]).is_err() {
+                return Err(format!("board {}'s feedback contradicts itself", board + 1));
+            }
+        }
+        self.guess_count += 1;
+        Ok(())
+    }
+
+    /// The boards that haven't been solved yet.
+    pub fn unsolved_boards(&self) -> Vec<usize> {
+        (0..self.boards.len())
+            .filter(|board| !self.is_solved(*board))
+            .collect()
+    }
+
+    /// Each board's state: solved, or the words that could still be its answer.
+    pub fn states(&self, words: &WordList<N>) -> Vec<BoardState<N>> {
+        (0..self.boards.len())
+            .map(|board| {
+                if let Some(guesses) = self.solved_in(board) {
+                    return BoardState::Solved(guesses);
+                }
+                match KnownWordConstraints::from_grid_checked(&self.boards[board]) {
+                    Ok(constraints) => BoardState::Open(words.filter_preview(&constraints)),
+                    Err(contradictions) => BoardState::Contradiction(contradictions),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn solved_boards_stop_taking_guesses() {
+        let mut game: MultiBoard<5> = MultiBoard::new(2, 7);
+        game.add_scored("CRANE", &["STAIR", "CRANE"]).unwrap();
+        assert_eq!(None, game.solved_in(0));
+        assert_eq!(Some(1), game.solved_in(1));
+        assert_eq!(vec![0], game.unsolved_boards());
+
+        game.add_scored("STAIR", &["STAIR", "CRANE"]).unwrap();
+        assert_eq!(Some(2), game.solved_in(0));
+        assert_eq!("     ", game.boards()[1].guesses()[1].word());
+        assert!(game.is_over());
+        assert!(game.add_scored("SLATE", &["STAIR", "CRANE"]).is_err());
+    }
+
+    #[test]
+    fn advance_checks_every_board() {
+        let mut game: MultiBoard<5> = MultiBoard::new(2, 7);
+        game.board_mut(0).guess_mut(0).set_scored("CRANE", "STAIR");
+        assert!(game.advance().is_err()); // board 2 isn't filled in
+
+        game.board_mut(1).guess_mut(0).set_scored("SLATE", "STAIR");
+        assert!(game.advance().is_err()); // different words
+
+        game.board_mut(1).guess_mut(0).set_scored("CRANE", "SHIRT");
+        game.advance().unwrap();
+        assert_eq!(1, game.guess_count());
+    }
+
+    #[test]
+    fn states() {
+        let words = WordList::from_words(&["CRANE", "STAIR", "SHIRT", "TRAIN"]);
+        let mut game: MultiBoard<5> = MultiBoard::new(3, 7);
+        game.add_scored("CRANE", &["STAIR", "CRANE", "SHIRT"])
+            .unwrap();
+        let states = game.states(&words);
+        let BoardState::Open(stair) = &states[0] else {
+            panic!("board 1 should be open")
+        };
+        let stair: Vec<&str> = stair.words().map(|w| w.word.as_str()).collect();
+        assert_eq!(vec!["STAIR"], stair);
+        assert!(matches!(states[1], BoardState::Solved(1)));
+        let BoardState::Open(shirt) = &states[2] else {
+            panic!("board 3 should be open")
+        };
+        let shirt: Vec<&str> = shirt.words().map(|w| w.word.as_str()).collect();
+        assert_eq!(vec!["SHIRT"], shirt);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::analyze::util::{chars_count, CharsCount};
+use crate::guess::known_word_constraints::CharKnowledge;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The feedback that one guess gets: one `CharKnowledge` per letter. This is the one place that
+/// Wordle's scoring rules live; everything else that needs to score a guess (the auto-guesser, the
+/// pattern-based analyzers, the game hosts) goes through `Pattern::score`.
+///
+/// A pattern can be written as a string of `CharKnowledge::feedback_char`s, like `GY.GB`, and it
+/// has a compact code (see `Pattern::code`) for using it as an index.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Pattern<const N: usize> {
+    knowledge: [CharKnowledge; N],
+}
+
+impl<const N: usize> Pattern<N> {
+    /// The number of distinct codes; see `Pattern::code`.
+    pub const COUNT: usize = 3_usize.pow(N as u32);
+
+    pub fn new(knowledge: [CharKnowledge; N]) -> Self {
+        Pattern { knowledge }
+    }
+
+    /// The feedback that `guess` gets if the answer is `answer`, case-insensitively.
+    ///
+    /// Each letter of the guess that's in the same place in the answer is correct. Then, going
+    /// from left to right, each other letter is in the wrong position if the answer has any of that
+    /// letter that haven't been matched up yet (by a correct letter, or by an earlier
+    /// wrong-position one), or missing otherwise. So if the answer has one E, a guess with two
+    /// gets one yellow E at most, and none at all if the other E is green.
+    pub fn score(guess: &str, answer: &str) -> Self {
+        let mut answer_chars = ['\x00'; N];
+        for (idx, ch) in answer.chars().take(N).enumerate() {
+            answer_chars[idx] = ch.to_ascii_uppercase();
+        }
+        let guess = guess.to_ascii_uppercase();
+        Self::score_counted(&guess, &answer_chars, &mut chars_count(answer.chars()))
+    }
+
+    /// Like `score`, but faster, for scoring lots of pairs: `guess` and `answer` must already be
+    /// uppercase, and `answer_chars_count` must have the counts of `answer`'s chars. This uses
+    /// `answer_chars_count` as scratch space, so it needs to be reset before the next call.
+    #[inline]
+    pub(crate) fn score_counted(
+        guess: &str,
+        answer: &[char; N],
+        answer_chars_count: &mut CharsCount,
+    ) -> Self {
+        let mut knowledge = [CharKnowledge::Missing; N];
+        // First, all the ones in the right position; they get first dibs on the answer's chars.
+        for (idx, guess_ch) in guess.chars().take(N).enumerate() {
+            if guess_ch == answer[idx] {
+                knowledge[idx] = CharKnowledge::Correct;
+                answer_chars_count.decrement(guess_ch);
+            }
+        }
+        // Now the ones in the wrong position, from whatever's left.
+        for (idx, guess_ch) in guess.chars().take(N).enumerate() {
+            if knowledge[idx] == CharKnowledge::Correct {
+                continue;
+            }
+            if let Some(remaining) = answer_chars_count.get_mut(guess_ch) {
+                if *remaining > 0 {
+                    knowledge[idx] = CharKnowledge::WrongPosition;
+                    *remaining -= 1;
+                }
+            }
+        }
+        Pattern { knowledge }
+    }
+
+    pub fn knowledge(&self) -> &[CharKnowledge; N] {
+        &self.knowledge
+    }
+
+    /// Whether every letter is correct: that is, the guess was the answer.
+    pub fn is_solved(&self) -> bool {
+        self.knowledge.iter().all(|k| *k == CharKnowledge::Correct)
+    }
+
+    /// A compact code for this pattern: an `N`-digit number in base 3, with one digit per position
+    /// (the first position is the least significant). Each digit is 0 for missing, 1 for the wrong
+    /// position and 2 for correct, so the codes go from 0 to `Pattern::COUNT - 1`. Unknown letters
+    /// count as missing.
+    #[inline]
+    pub fn code(&self) -> usize {
+        let mut result = 0;
+        let mut position_factor = 1;
+        for knowledge in self.knowledge {
+            let digit = match knowledge {
+                CharKnowledge::Missing | CharKnowledge::Unknown => 0,
+                CharKnowledge::WrongPosition => 1,
+                CharKnowledge::Correct => 2,
+            };
+            result += digit * position_factor;
+            position_factor *= 3;
+        }
+        result
+    }
+
+    /// The inverse of `code`, or `None` if `code` is out of range.
+    pub fn from_code(mut code: usize) -> Option<Self> {
+        if code >= Self::COUNT {
+            return None;
+        }
+        let mut knowledge = [CharKnowledge::Missing; N];
+        for k in knowledge.iter_mut() {
+            *k = match code % 3 {
+                0 => CharKnowledge::Missing,
+                1 => CharKnowledge::WrongPosition,
+                _ => CharKnowledge::Correct,
+            };
+            code /= 3;
+        }
+        Some(Pattern { knowledge })
+    }
+}
+
+/// Writes the pattern as its `CharKnowledge::feedback_char`s, like `GY.GB`.
+impl<const N: usize> Display for Pattern<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for knowledge in self.knowledge {
+            write!(f, "{}", knowledge.feedback_char())?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses feedback chars (see `CharKnowledge::from_feedback_char`), or `.` for unknown.
+impl<const N: usize> FromStr for Pattern<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Emoji squares are often followed by a variation selector; that's not a char of its own.
+        let chars: Vec<char> = s.chars().filter(|c| *c != '\u{FE0F}').collect();
+        if chars.len() != N {
+            return Err(format!(
+                "expected {} chars of feedback, but found {} in \"{}\"",
+                N,
+                chars.len(),
+                s
+            ));
+        }
+        let mut knowledge = [CharKnowledge::Unknown; N];
+        for (idx, ch) in chars.into_iter().enumerate() {
+            knowledge[idx] = match ch {
+                '.' => CharKnowledge::Unknown,
+                ch => CharKnowledge::from_feedback_char(ch)
+                    .ok_or_else(|| format!("invalid feedback \"{}\" in \"{}\"", ch, s))?,
+            };
+        }
+        Ok(Pattern { knowledge })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn duplicate_letters() {
+        for (guess, answer, expected) in [
+            // No duplicates at all.
+            ("CRANE", "STAIR", "BYGBB"),
+            ("STAIR", "STAIR", "GGGGG"),
+            // Two in the guess, one in the answer: only the first gets a yellow...
+            ("SPEED", "ABIDE", "BBYBY"),
+            // ... unless one of them is green, which takes it, even if it comes later.
+            ("EERIE", "CRANE", "BBYBG"),
+            ("ALLOT", "CLAIM", "YGBBB"),
+            ("LEVEL", "HOTEL", "BBBGG"),
+            // Two in the guess, two in the answer.
+            ("EERIE", "EMCEE", "GYBBG"),
+            ("SPEED", "ERASE", "YBYYB"),
+            ("TASTE", "STATE", "YYYGG"),
+            ("LLAMA", "LLAMA", "GGGGG"),
+            // Three in the guess, fewer in the answer.
+            ("EERIE", "ELDER", "GYYBB"),
+            ("MAMMA", "MAXIM", "GGYBB"),
+            ("SASSY", "ASSET", "YYGBB"),
+            // One in the guess, two in the answer.
+            ("CRANE", "EERIE", "BYBBG"),
+        ] {
+            let pattern: Pattern<5> = Pattern::score(guess, answer);
+            assert_eq!(
+                expected,
+                pattern.to_string(),
+                "{} against {}",
+                guess,
+                answer
+            );
+        }
+    }
+
+    /// A correct letter is never downgraded to a yellow, even if that same letter also appears
+    /// earlier in the guess.
+    #[test]
+    fn correct_is_never_overwritten() {
+        let pattern: Pattern<3> = Pattern::score("AAB", "ABA");
+        assert_eq!("GYY", pattern.to_string());
+        let pattern: Pattern<3> = Pattern::score("BAA", "AAB");
+        assert_eq!("YGY", pattern.to_string());
+    }
+
+    #[test]
+    fn case_and_other_chars() {
+        let upper: Pattern<5> = Pattern::score("CRANE", "STAIR");
+        assert_eq!(upper, Pattern::score("crane", "Stair"));
+        let pattern: Pattern<5> = Pattern::score("CR-NE", "STAIR");
+        assert_eq!("BYBBB", pattern.to_string());
+    }
+
+    /// Compares `score` against a simpler (but slower) statement of the rules, for every pair of
+    /// 4-letter words made of A, B and C.
+    #[test]
+    fn exhaustive_against_reference() {
+        let letters = ['A', 'B', 'C'];
+        let words: Vec<String> = (0..3_usize.pow(4))
+            .map(|mut n| {
+                (0..4)
+                    .map(|_| {
+                        let ch = letters[n % 3];
+                        n /= 3;
+                        ch
+                    })
+                    .collect()
+            })
+            .collect();
+        for guess in &words {
+            for answer in &words {
+                let pattern: Pattern<4> = Pattern::score(guess, answer);
+                assert_eq!(
+                    reference_score(guess, answer),
+                    pattern.to_string(),
+                    "{} against {}",
+                    guess,
+                    answer
+                );
+            }
+        }
+    }
+
+    /// For each letter: the answer's copies of it that aren't matched by a green can each turn one
+    /// non-green copy in the guess yellow, from left to right.
+    fn reference_score(guess: &str, answer: &str) -> String {
+        let guess: Vec<char> = guess.chars().collect();
+        let answer: Vec<char> = answer.chars().collect();
+        let mut result: Vec<char> = guess
+            .iter()
+            .zip(&answer)
+            .map(|(g, a)| if g == a { 'G' } else { 'B' })
+            .collect();
+        for letter in ['A', 'B', 'C'] {
+            let unmatched = (0..answer.len())
+                .filter(|&i| answer[i] == letter && result[i] != 'G')
+                .count();
+            let yellows: Vec<usize> = (0..guess.len())
+                .filter(|&i| guess[i] == letter && result[i] != 'G')
+                .take(unmatched)
+                .collect();
+            for i in yellows {
+                result[i] = 'Y';
+            }
+        }
+        result.into_iter().collect()
+    }
+
+    #[test]
+    fn codes() {
+        for code in 0..Pattern::<5>::COUNT {
+            let pattern: Pattern<5> = Pattern::from_code(code).unwrap();
+            assert_eq!(code, pattern.code());
+        }
+        assert_eq!(None, Pattern::<5>::from_code(243));
+        let solved: Pattern<5> = "GGGGG".parse().unwrap();
+        assert_eq!(242, solved.code());
+        assert!(solved.is_solved());
+        let first_yellow: Pattern<5> = "YBBBB".parse().unwrap();
+        assert_eq!(1, first_yellow.code());
+    }
+
+    #[test]
+    fn strings() {
+        let pattern: Pattern<5> = "GY.gb".parse().unwrap();
+        assert_eq!(
+            &[
+                CharKnowledge::Correct,
+                CharKnowledge::WrongPosition,
+                CharKnowledge::Unknown,
+                CharKnowledge::Correct,
+                CharKnowledge::Missing
+            ],
+            pattern.knowledge()
+        );
+        assert_eq!("GY.GB", pattern.to_string());
+        // Unknown letters don't have codes of their own.
+        assert_eq!(
+            "GYBGB".parse::<Pattern<5>>().unwrap().code(),
+            pattern.code()
+        );
+
+        let emoji: Pattern<5> = "⬛️🟨🟩⬛️⬛️".parse().unwrap();
+        assert_eq!("BYGBB", emoji.to_string());
+        assert!("GYGB".parse::<Pattern<5>>().is_err());
+        assert!("GYGBX".parse::<Pattern<5>>().is_err());
+    }
+}
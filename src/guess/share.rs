@@ -0,0 +1,339 @@
+use crate::guess::guesses::{GuessGrid, GuessStr};
+use crate::guess::known_word_constraints::CharKnowledge;
+use crate::word_list::WordList;
+use std::fmt::{Display, Formatter};
+
+/// The number of guesses to assume if the share text doesn't have a title line.
+const DEFAULT_MAX_GUESSES: usize = 6;
+
+/// The text that Wordle produces when you share a game: a title line like `Wordle 1,234 4/6`,
+/// followed by one row of emoji squares per guess. It doesn't include the guessed words themselves.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ShareText<const N: usize> {
+    /// Everything on the title line before the score, such as `Wordle 1,234`.
+    pub title: String,
+    /// How many guesses it took, or `None` if the puzzle wasn't solved.
+    pub solved_in: Option<usize>,
+    pub max_guesses: usize,
+    /// Hard mode games have a `*` after the score.
+    pub hard_mode: bool,
+    pub rows: Vec<[CharKnowledge; N]>,
+}
+
+impl<const N: usize> ShareText<N> {
+    /// Parses share text. Blank lines, and anything else after the title that isn't a row of
+    /// squares (such as a link), are ignored. If there's no title line, the score is inferred from
+    /// the rows.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut title = None;
+        let mut rows = Vec::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if title.is_none() && rows.is_empty() {
+                if let Some(parsed) = parse_title(line)? {
+                    title = Some(parsed);
+                    continue;
+                }
+            }
+            if let Some(row) = parse_row(line) {
+                rows.push(row);
+            }
+        }
+        if rows.is_empty() {
+            return Err("no rows of feedback found".to_string());
+        }
+        let solved = rows
+            .last()
+            .map(|row| row.iter().all(|k| *k == CharKnowledge::Correct))
+            .unwrap_or(false);
+        let Some(Title {
+            title,
+            solved_in,
+            max_guesses,
+            hard_mode,
+        }) = title
+        else {
+            return Ok(ShareText {
+                title: "Wordle".to_string(),
+                solved_in: if solved { Some(rows.len()) } else { None },
+                max_guesses: DEFAULT_MAX_GUESSES.max(rows.len()),
+                hard_mode: false,
+                rows,
+            });
+        };
+        if rows.len() != solved_in.unwrap_or(max_guesses) || solved_in.is_some() != solved {
+            return Err(format!(
+                "the score says {}/{}, but there are {} rows{}",
+                score_str(solved_in),
+                max_guesses,
+                rows.len(),
+                if solved {
+                    ""
+                } else {
+                    " and the last isn't solved"
+                },
+            ));
+        }
+        Ok(ShareText {
+            title,
+            solved_in,
+            max_guesses,
+            hard_mode,
+            rows,
+        })
+    }
+
+    /// Creates share text for the given (filled-in) rows. The game counts as solved if the last
+    /// row is all correct.
+    pub fn from_rows(
+        title: &str,
+        rows: &[GuessStr<N>],
+        max_guesses: usize,
+        hard_mode: bool,
+    ) -> Self {
+        let rows: Vec<[CharKnowledge; N]> = rows
+            .iter()
+            .map(|row| row.guesses().clone().map(|g| g.knowledge()))
+            .collect();
+        let solved = rows
+            .last()
+            .map(|row| row.iter().all(|k| *k == CharKnowledge::Correct))
+            .unwrap_or(false);
+        ShareText {
+            title: title.to_string(),
+            solved_in: if solved { Some(rows.len()) } else { None },
+            max_guesses,
+            hard_mode,
+            rows,
+        }
+    }
+
+    /// Creates a grid of `row_count` rows from these rows, given the words that were guessed.
+    pub fn to_grid_with_guesses<S: AsRef<str>>(
+        &self,
+        guesses: &[S],
+        row_count: usize,
+    ) -> Result<GuessGrid<N>, String> {
+        if guesses.len() != self.rows.len() {
+            return Err(format!(
+                "there are {} rows of feedback, but {} guessed words",
+                self.rows.len(),
+                guesses.len()
+            ));
+        }
+        let rows = self
+            .rows
+            .iter()
+            .zip(guesses)
+            .map(|(knowledge, guess)| {
+                let feedback: String = knowledge.iter().map(|k| k.feedback_char()).collect();
+                format!("{}:{}", guess.as_ref(), feedback).parse()
+            })
+            .collect::<Result<Vec<GuessStr<N>>, String>>()?;
+        GuessGrid::from_rows(rows, row_count)
+    }
+
+    /// Creates a grid from these rows, given only the answer. Since the share text doesn't say what
+    /// the guesses were, this picks the first word in `words_list` (that is, the most common one)
+    /// that would have gotten each row's feedback. The result is a plausible game, but not
+    /// necessarily the one that was actually played. Like `to_grid_with_guesses`, the grid has
+    /// `row_count` rows.
+    pub fn to_grid_with_answer(
+        &self,
+        answer: &str,
+        words_list: &WordList<N>,
+        row_count: usize,
+    ) -> Result<GuessGrid<N>, String> {
+        if answer.chars().count() != N {
+            return Err(format!(
+                "expected a {}-letter word, but got \"{}\"",
+                N, answer
+            ));
+        }
+        let mut rows = Vec::with_capacity(self.rows.len());
+        for (row_idx, knowledge) in self.rows.iter().enumerate() {
+            let mut candidate = GuessStr::new();
+            let found = words_list.words().any(|wf| {
+                candidate.set_scored(&wf.word, answer);
+                candidate
+                    .guesses()
+                    .iter()
+                    .map(|g| g.knowledge())
+                    .eq(*knowledge)
+            }) || {
+                // The answer might not be in the words list, but it's always a valid guess.
+                candidate.set_scored(answer, answer);
+                candidate
+                    .guesses()
+                    .iter()
+                    .map(|g| g.knowledge())
+                    .eq(*knowledge)
+            };
+            if !found {
+                return Err(format!(
+                    "no word in the list would get row {}'s feedback for the answer {}",
+                    row_idx + 1,
+                    answer.to_ascii_uppercase()
+                ));
+            }
+            rows.push(candidate);
+        }
+        GuessGrid::from_rows(rows, row_count)
+    }
+}
+
+impl<const N: usize> Display for ShareText<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}/{}{}",
+            self.title,
+            score_str(self.solved_in),
+            self.max_guesses,
+            if self.hard_mode { "*" } else { "" }
+        )?;
+        writeln!(f)?;
+        writeln!(f)?;
+        for row in &self.rows {
+            for knowledge in row {
+                write!(f, "{}", knowledge.emoji())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn score_str(solved_in: Option<usize>) -> String {
+    match solved_in {
+        Some(n) => n.to_string(),
+        None => "X".to_string(),
+    }
+}
+
+/// A parsed title line; see the fields of the same names in `ShareText`.
+struct Title {
+    title: String,
+    solved_in: Option<usize>,
+    max_guesses: usize,
+    hard_mode: bool,
+}
+
+/// Parses a title line like `Wordle 1,234 4/6*`. Returns `Ok(None)` if this doesn't look like a
+/// title line at all.
+fn parse_title(line: &str) -> Result<Option<Title>, String> {
+    let Some((title, score)) = line.rsplit_once(char::is_whitespace) else {
+        return Ok(None);
+    };
+    let (score, hard_mode) = match score.strip_suffix('*') {
+        Some(score) => (score, true),
+        None => (score, false),
+    };
+    let Some((solved_in, max_guesses)) = score.split_once('/') else {
+        return Ok(None);
+    };
+    let Ok(max_guesses) = max_guesses.parse::<usize>() else {
+        return Ok(None);
+    };
+    let solved_in = match solved_in {
+        "X" | "x" => None,
+        n => match n.parse::<usize>() {
+            Ok(n) if n >= 1 && n <= max_guesses => Some(n),
+            _ => return Err(format!("invalid score in title: {}", line)),
+        },
+    };
+    Ok(Some(Title {
+        title: title.trim().to_string(),
+        solved_in,
+        max_guesses,
+        hard_mode,
+    }))
+}
+
+/// Parses a row of squares. Returns `None` if this isn't a row of exactly `N` squares (or letters,
+/// like `GYBBB`): a stray line like "gg" is just text, not a row of the wrong length.
+fn parse_row<const N: usize>(line: &str) -> Option<[CharKnowledge; N]> {
+    let knowledge: Vec<CharKnowledge> = line
+        .chars()
+        .filter(|c| *c != '\u{FE0F}' && !c.is_whitespace())
+        .map(CharKnowledge::from_feedback_char)
+        .collect::<Option<_>>()?;
+    knowledge.try_into().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SHARE: &str = "Wordle 1,234 3/6*
+
+⬛🟨🟩⬛⬛
+🟨🟨🟨⬛🟨
+🟩🟩🟩🟩🟩
+";
+
+    #[test]
+    fn parse() {
+        let share: ShareText<5> = ShareText::parse(SHARE).unwrap();
+        assert_eq!("Wordle 1,234", share.title);
+        assert_eq!(Some(3), share.solved_in);
+        assert_eq!(6, share.max_guesses);
+        assert!(share.hard_mode);
+        assert_eq!(3, share.rows.len());
+    }
+
+    #[test]
+    fn parse_errors() {
+        // wrong number of rows for the score
+        assert!(ShareText::<5>::parse("Wordle 1 2/6\n🟩🟩🟩🟩🟩").is_err());
+        // last row isn't solved, but the score says it is
+        assert!(ShareText::<5>::parse("Wordle 1 1/6\n🟩🟩🟩🟩⬛").is_err());
+        // the only row is the wrong length, so it isn't a row at all
+        assert!(ShareText::<5>::parse("Wordle 1 1/6\n🟩🟩🟩🟩").is_err());
+        // no rows
+        assert!(ShareText::<5>::parse("Wordle 1 1/6").is_err());
+    }
+
+    #[test]
+    fn stray_lines() {
+        let text = SHARE.replace("\n\n", "\ngg\n\n") + "bgy\n";
+        let share: ShareText<5> = ShareText::parse(&text).unwrap();
+        assert_eq!(ShareText::parse(SHARE).unwrap(), share);
+    }
+
+    #[test]
+    fn parse_without_title() {
+        let share: ShareText<5> = ShareText::parse("⬛🟨🟩⬛⬛\n🟨🟨🟨⬛🟨").unwrap();
+        assert_eq!(None, share.solved_in);
+        assert_eq!(2, share.rows.len());
+    }
+
+    #[test]
+    fn round_trip() {
+        let share: ShareText<5> = ShareText::parse(SHARE).unwrap();
+        let grid: GuessGrid<5> = share
+            .to_grid_with_guesses(&["CRANE", "TARES", "STAIR"], 6)
+            .unwrap();
+        assert_eq!("TARES", grid.guesses()[1].word());
+        assert_eq!("YYYBY", grid.guesses()[1].feedback());
+
+        let rendered = ShareText::from_rows("Wordle 1,234", &grid.guesses()[..3], 6, true);
+        assert_eq!(share, rendered);
+        assert_eq!(SHARE.replace('⬛', "⬛\u{FE0F}"), rendered.to_string());
+    }
+
+    #[test]
+    fn grid_with_answer() {
+        let share: ShareText<5> = ShareText::parse(SHARE).unwrap();
+        let words = WordList::from_words(&["HOUSE", "CRANE", "TARES", "STAIR"]);
+        let grid = share.to_grid_with_answer("stair", &words, 6).unwrap();
+        let guessed: Vec<String> = grid.guesses()[..3].iter().map(|g| g.word()).collect();
+        assert_eq!(vec!["CRANE", "TARES", "STAIR"], guessed);
+
+        for wrong_length in ["STAIRS", "STAI"] {
+            let Err(err) = share.to_grid_with_answer(wrong_length, &words, 6) else {
+                panic!("{} isn't a 5-letter word", wrong_length);
+            };
+            assert!(err.starts_with("expected a 5-letter word"), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::util::data_dir;
+
+/// One finished game, as it's recorded in the history file. The file has one of these per line,
+/// as JSON, oldest first.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// When the game finished, as `YYYY-MM-DD` (in UTC).
+    pub date: String,
+    pub mode: GameMode,
+    /// The answer, if it's known: when playing, the host always knows it; when using the helper,
+    /// it's only known if the game was won.
+    pub answer: Option<String>,
+    pub won: bool,
+    pub guesses: Vec<RecordedGuess>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Display, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum GameMode {
+    /// The guesses and feedback came from a game played elsewhere.
+    Helper,
+    Play,
+    Absurdle,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RecordedGuess {
+    pub word: String,
+    /// See `GuessStr::feedback`.
+    pub feedback: String,
+    /// What each analyzer would have guessed here, given the guesses before this one.
+    pub top_picks: Vec<TopPick>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TopPick {
+    pub analyzer: String,
+    pub word: String,
+}
+
+/// Where the history goes if `--history` isn't given: `history.jsonl`, in the `data_dir`.
+pub fn default_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("history.jsonl"))
+}
+
+/// Reads all of the games from the history file. A missing file is just an empty history.
+pub fn load(path: &Path) -> Result<Vec<GameRecord>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text).map_err(|e| format!("invalid history {}: {}", path.display(), e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("couldn't read {}: {}", path.display(), e)),
+    }
+}
+
+/// Adds `record` to the end of the history file, creating the file (and its directory) if needed.
+pub fn append(path: &Path, record: &GameRecord) -> Result<(), String> {
+    let err =
+        |e: std::io::Error| format!("couldn't save game history to {}: {}", path.display(), e);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(err)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(err)?;
+    writeln!(file, "{}", to_line(record)).map_err(err)
+}
+
+fn to_line(record: &GameRecord) -> String {
+    serde_json::to_string(record).expect("game records always serialize")
+}
+
+fn parse(text: &str) -> Result<Vec<GameRecord>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line).map_err(|e| format!("line {}: {}", idx + 1, e))
+        })
+        .collect()
+}
+
+/// Statistics over a history, like Wordle's own stats screen, plus how often the user's guesses
+/// matched each analyzer's top pick.
+#[derive(PartialEq, Debug)]
+pub struct Stats {
+    pub games: usize,
+    pub wins: usize,
+    /// How many games in a row have been won, up to the most recent one.
+    pub current_streak: usize,
+    pub max_streak: usize,
+    /// How many games were won in each number of guesses: the first entry is for 1 guess.
+    pub distribution: Vec<usize>,
+    pub analyzers: Vec<AnalyzerMatches>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct AnalyzerMatches {
+    pub name: String,
+    /// How many guesses this analyzer had a top pick for.
+    pub guesses: usize,
+    /// How many of those guesses were that pick.
+    pub matched: usize,
+}
+
+impl Stats {
+    /// Separate stats for each mode that has any games: a helper game's streak and guesses don't
+    /// say much about how you play against the host, or against Absurdle.
+    pub fn by_mode(records: &[GameRecord]) -> Vec<(GameMode, Stats)> {
+        GameMode::iter()
+            .filter_map(|mode| {
+                let games: Vec<GameRecord> =
+                    records.iter().filter(|r| r.mode == mode).cloned().collect();
+                (!games.is_empty()).then(|| (mode, Stats::from_records(&games)))
+            })
+            .collect()
+    }
+
+    pub fn from_records(records: &[GameRecord]) -> Self {
+        let mut stats = Stats {
+            games: records.len(),
+            wins: 0,
+            current_streak: 0,
+            max_streak: 0,
+            distribution: Vec::new(),
+            analyzers: Vec::new(),
+        };
+        for record in records {
+            if record.won {
+                stats.wins += 1;
+                stats.current_streak += 1;
+                stats.max_streak = stats.max_streak.max(stats.current_streak);
+                let guess_count = record.guesses.len().max(1);
+                if stats.distribution.len() < guess_count {
+                    stats.distribution.resize(guess_count, 0);
+                }
+                stats.distribution[guess_count - 1] += 1;
+            } else {
+                stats.current_streak = 0;
+            }
+            for guess in &record.guesses {
+                for pick in &guess.top_picks {
+                    let idx = match stats.analyzers.iter().position(|a| a.name == pick.analyzer) {
+                        Some(idx) => idx,
+                        None => {
+                            stats.analyzers.push(AnalyzerMatches {
+                                name: pick.analyzer.clone(),
+                                guesses: 0,
+                                matched: 0,
+                            });
+                            stats.analyzers.len() - 1
+                        }
+                    };
+                    stats.analyzers[idx].guesses += 1;
+                    if pick.word == guess.word {
+                        stats.analyzers[idx].matched += 1;
+                    }
+                }
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let records = vec![
+            record(
+                true,
+                &[("CRANE", "BYGBB", "CRANE"), ("STAIR", "GGGGG", "STAIR")],
+            ),
+            record(false, &[("SLATE", "BBBBB", "CRANE")]),
+        ];
+        let text: String = records.iter().map(|r| to_line(r) + "\n").collect();
+        assert_eq!(records, parse(&text).unwrap());
+        assert_eq!(records, parse(&(text + "\n")).unwrap());
+
+        assert!(parse("{\"date\": \"2024-01-01\"}")
+            .unwrap_err()
+            .starts_with("line 1:"));
+    }
+
+    #[test]
+    fn stats() {
+        let records = vec![
+            record(
+                true,
+                &[("CRANE", "BYGBB", "CRANE"), ("STAIR", "GGGGG", "TRAIN")],
+            ),
+            record(
+                true,
+                &[("SLATE", "BYBBB", "CRANE"), ("STAIR", "GGGGG", "STAIR")],
+            ),
+            record(false, &[("CRANE", "BBBBB", "CRANE")]),
+            record(true, &[("CRANE", "GGGGG", "CRANE")]),
+        ];
+        let stats = Stats::from_records(&records);
+        assert_eq!(4, stats.games);
+        assert_eq!(3, stats.wins);
+        assert_eq!(1, stats.current_streak);
+        assert_eq!(2, stats.max_streak);
+        assert_eq!(vec![1, 2], stats.distribution);
+        assert_eq!(
+            vec![AnalyzerMatches {
+                name: "Entropy".to_string(),
+                guesses: 6,
+                matched: 4,
+            }],
+            stats.analyzers
+        );
+    }
+
+    #[test]
+    fn stats_by_mode() {
+        let absurdle = GameRecord {
+            mode: GameMode::Absurdle,
+            ..record(false, &[("CRANE", "BBBBB", "CRANE")])
+        };
+        let records = vec![
+            record(true, &[("CRANE", "GGGGG", "CRANE")]),
+            absurdle.clone(),
+            record(true, &[("SLATE", "GGGGG", "CRANE")]),
+        ];
+        let by_mode = Stats::by_mode(&records);
+        let modes: Vec<GameMode> = by_mode.iter().map(|(mode, _)| *mode).collect();
+        assert_eq!(vec![GameMode::Play, GameMode::Absurdle], modes);
+        assert_eq!(2, by_mode[0].1.current_streak);
+        assert_eq!(Stats::from_records(&[absurdle]), by_mode[1].1);
+        assert!(Stats::by_mode(&[]).is_empty());
+    }
+
+    /// A game whose guesses are `(word, feedback, the analyzer's top pick)`.
+    fn record(won: bool, guesses: &[(&str, &str, &str)]) -> GameRecord {
+        GameRecord {
+            date: "2024-01-01".to_string(),
+            mode: GameMode::Play,
+            answer: Some("STAIR".to_string()),
+            won,
+            guesses: guesses
+                .iter()
+                .map(|(word, feedback, pick)| RecordedGuess {
+                    word: word.to_string(),
+                    feedback: feedback.to_string(),
+                    top_picks: vec![TopPick {
+                        analyzer: "Entropy".to_string(),
+                        word: pick.to_string(),
+                    }],
+                })
+                .collect(),
+        }
+    }
+}
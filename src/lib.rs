@@ -1,5 +1,9 @@
 pub mod analyze;
+pub mod external_words;
 pub mod guess;
+pub mod history;
+pub mod session;
 pub mod ui;
 pub mod util;
 pub mod word_list;
+pub mod word_list_config;
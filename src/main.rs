@@ -1,48 +1,808 @@
-use std::env;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::collections::HashSet;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{io, process};
 use wordlehelper::analyze::analyzer;
-use wordlehelper::analyze::auto_guesser::AutoGuesser;
-use wordlehelper::guess::known_word_constraints::CharKnowledge;
-use wordlehelper::ui::tui::MainWindow;
-use wordlehelper::word_list::WordList;
+use wordlehelper::analyze::analyzer::{Analyzer, ScoredWord};
+use wordlehelper::analyze::auto_guesser::{AutoGuesser, ResultsByWord};
+use wordlehelper::analyze::pattern_matrix::PatternMatrix;
+use wordlehelper::analyze::{results_output, strategy_stats};
+use wordlehelper::external_words::{load_words_file, WordsFileSpec};
+use wordlehelper::guess::guesses::{GuessGrid, GuessStr};
+use wordlehelper::guess::host::{daily_seed, Absurdle, FixedAnswer};
+use wordlehelper::guess::known_word_constraints::KnownWordConstraints;
+use wordlehelper::guess::share::ShareText;
+use wordlehelper::history;
+use wordlehelper::history::{GameMode, Stats};
+use wordlehelper::session;
+use wordlehelper::session::Session;
+use wordlehelper::ui::tui::{GameOptions, MainWindow};
+use wordlehelper::word_list::{WordFreq, WordList};
+use wordlehelper::word_list_config::{EmbeddedSources, Normalize, SourceConfig, WordListConfig};
+
+/// Interactive CLI tool for solving Wordle puzzles.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    options: CommonOptions,
+}
+
+#[derive(Args)]
+struct CommonOptions {
+    /// Number of letters in each word. The embedded word lists only have 5-letter words, so other
+    /// lengths need --words-file
+    #[arg(long, global = true, default_value_t = 5)]
+    length: usize,
+    /// Number of guesses allowed [default: 6, or 5 more than the number of boards]
+    #[arg(long, global = true)]
+    rows: Option<usize>,
+    /// Play on this many boards at once, like Dordle (2), Quordle (4) or Octordle (8). Only the TUI
+    /// supports more than one board, and not in hard mode.
+    #[arg(long, global = true, default_value_t = 1)]
+    boards: usize,
+    /// Read the word list configuration (sources, weights, limit and normalization) from this JSON
+    /// file; the other word list options override it
+    #[arg(long, global = true, value_name = "FILE")]
+    words_config: Option<PathBuf>,
+    /// Which embedded word list to use, as NAME[=WEIGHT]: std (all of them, at their standard
+    /// weights), gutenberg, norvig, hermitdave or none. May be repeated. [default: std]
+    #[arg(long, global = true, value_name = "NAME")]
+    words: Vec<EmbeddedSources>,
+    /// Also use the words from this file, as [FORMAT:]PATH[=WEIGHT]. FORMAT is freq (word<TAB>freq
+    /// per line), plain (one word per line, most common first) or answers (the official answers
+    /// list); the default is freq if the file has tabs, or plain otherwise. May be repeated.
+    #[arg(long, global = true, value_name = "SPEC")]
+    words_file: Vec<WordsFileSpec>,
+    /// Only these words can be answers, as [FORMAT:]PATH (as for --words-file, but with no
+    /// weight). They can all be guessed, even if they aren't in the word list. [default: any word
+    /// in the word list]
+    #[arg(long, global = true, value_name = "SPEC")]
+    answers_file: Option<WordsFileSpec>,
+    /// Maximum number of words to take from the combined word list [default: 7500]
+    #[arg(long, global = true)]
+    words_limit: Option<usize>,
+    /// How to scale each word list's frequencies before weighting them: total, max, rank or none
+    /// [default: total]
+    #[arg(long, global = true, value_name = "MODE")]
+    normalize: Option<Normalize>,
+    /// Only use analyzers whose names contain this (case-insensitive). May be repeated.
+    #[arg(long = "analyzer", short, global = true)]
+    analyzers: Vec<String>,
+    /// Play by hard mode's rules: every guess must use all of the hints revealed so far
+    #[arg(long, global = true)]
+    hard: bool,
+    /// Save the precomputed guess/answer patterns in this directory, and reuse them next time the
+    /// same word list is used
+    #[arg(long, global = true, value_name = "DIR")]
+    pattern_cache: Option<PathBuf>,
+    /// Record finished games in this file, and read it for `stats`
+    /// [default: $XDG_DATA_HOME/wordlehelper/history.jsonl]
+    #[arg(long, global = true, value_name = "FILE")]
+    history: Option<PathBuf>,
+    /// Don't record finished games
+    #[arg(long, global = true, conflicts_with = "history")]
+    no_history: bool,
+}
+
+impl CommonOptions {
+    /// Where to record finished games, if anywhere.
+    fn history_path(&self) -> Option<PathBuf> {
+        if self.no_history {
+            return None;
+        }
+        self.history.clone().or_else(history::default_path)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Interactive helper: enter your guesses and their feedback, and see suggestions (the default)
+    Tui(TuiOptions),
+    /// Auto-guess the given answers with each analyzer
+    Solve {
+        /// The answers to guess; each one is a separate game
+        #[arg(required = true)]
+        answers: Vec<String>,
+        #[command(flatten)]
+        run: RunOptions,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Auto-guess every possible answer with each analyzer, and report how they did. The answers
+    /// are the words in --answers-file, or else the whole word list.
+    Simulate {
+        /// Only play this many games, using the first answers (the most common ones, unless
+        /// --answers-file is in the answers format)
+        #[arg(long)]
+        games: Option<usize>,
+        #[command(flatten)]
+        run: RunOptions,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Summary)]
+        format: OutputFormat,
+    },
+    /// Play Wordle: guess the word that's been picked, and each guess gets its feedback
+    Play {
+        /// Pick the answer from this many of the most common words
+        #[arg(long, default_value_t = 2500)]
+        answers: usize,
+        /// Pick the answer with this seed; the same seed always picks the same answer
+        #[arg(long, conflicts_with = "daily")]
+        seed: Option<u64>,
+        /// Pick today's answer, which is the same all day (in UTC)
+        #[arg(long)]
+        daily: bool,
+        #[command(flatten)]
+        hints: HintsOption,
+    },
+    /// Play Absurdle, where the answer changes after each guess to be as hard to find as it can be
+    Absurdle {
+        /// Have each analyzer play instead, and report how it did: that's its worst case
+        #[arg(long)]
+        auto: bool,
+        #[command(flatten)]
+        hints: HintsOption,
+        #[command(flatten)]
+        run: RunOptions,
+        /// The output format, with --auto
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Print each analyzer's top suggestions for the next guess
+    Suggest {
+        /// The guesses so far, each with its feedback: for example, CRANE:BYGBB or CRANE:⬛🟨🟩⬛⬛
+        /// (G for green, Y for yellow, B for black/grey)
+        guesses: Vec<String>,
+        /// How many suggestions to print, per analyzer
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Explain why a word isn't a possible answer, given the guesses so far
+    Explain {
+        /// The word to explain
+        word: String,
+        /// The guesses so far, each with its feedback, as for `suggest`
+        guesses: Vec<String>,
+    },
+    /// Read Wordle share text from stdin, and print its guesses in the form that `suggest` takes
+    Share {
+        /// The words that were guessed, in order
+        #[arg(long, num_args = 1.., conflicts_with = "answer", required_unless_present = "answer")]
+        guesses: Vec<String>,
+        /// The answer; each row's guess will be the most common word that gets that row's feedback
+        #[arg(long)]
+        answer: Option<String>,
+    },
+    /// Print the word list, with each word's frequency
+    Wordlist,
+    /// Print statistics about the games in the history, for each mode (helper, play or absurdle):
+    /// streaks, the guess distribution, and how often each guess was an analyzer's top pick
+    Stats,
+}
+
+#[derive(Args, Default)]
+struct TuiOptions {
+    /// Pick up the puzzle from the last session, without asking
+    #[arg(long)]
+    resume: bool,
+    /// Start a new puzzle, without asking whether to resume the last session
+    #[arg(long, conflicts_with = "resume")]
+    new: bool,
+    /// Save the puzzle in progress to this file after each change, and resume from it
+    /// [default: $XDG_DATA_HOME/wordlehelper/session-NxR.json, for N letters and R rows]
+    #[arg(long, value_name = "FILE")]
+    session: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct HintsOption {
+    /// Show the analyzers' suggestions from the start, instead of waiting for ctrl-g
+    #[arg(long)]
+    hints: bool,
+}
+
+#[derive(Args)]
+struct RunOptions {
+    /// How many threads to run games on (0 means one per core)
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum OutputFormat {
+    /// Each game's guesses, as emoji
+    Text,
+    /// Per-analyzer win rates and guess-count histograms
+    Summary,
+    /// One JSON object per game
+    Jsonl,
+    /// One CSV row per game
+    Csv,
+    /// Each game as Wordle share text
+    Share,
+}
 
 fn main() {
-    let try_words: Vec<String> = env::args().skip(1).collect();
-    if try_words.is_empty() {
-        let mut main_window: MainWindow<5, 6> = MainWindow::init();
-        main_window.run_main_loop();
-    } else {
-        let count = try_words.len();
-        let auto_guesser: AutoGuesser<5, 6> = AutoGuesser {
-            answer_words: try_words,
-            words_list: WordList::std(),
-            analyzers: analyzer::standard_suite(),
-        };
-        for result in auto_guesser.guess_all() {
-            if count > 0 {
-                println!("{}:", result.answer);
+    let cli = Cli::parse();
+    if let Err(err) = run_with_length(cli) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run_with_length(cli: Cli) -> Result<(), String> {
+    match cli.options.length {
+        4 => run::<4>(cli),
+        5 => run::<5>(cli),
+        6 => run::<6>(cli),
+        7 => run::<7>(cli),
+        8 => run::<8>(cli),
+        other => Err(format!(
+            "words must have between 4 and 8 letters (got {})",
+            other
+        )),
+    }
+}
+
+fn run<const N: usize>(cli: Cli) -> Result<(), String> {
+    let options = cli.options;
+    // Each extra board gets one more guess, as in Dordle, Quordle and Octordle.
+    let rows = options.rows.unwrap_or(6 + options.boards.saturating_sub(1));
+    if !(1..=13).contains(&rows) {
+        return Err(format!("rows must be between 1 and 13 (got {})", rows));
+    }
+    let words_list: WordList<N> = load_words(&options)?;
+    let (words_list, answers_list) = load_answers(words_list, &options)?;
+    let analyzers = analyzer::select_from_standard_suite(&options.analyzers)?;
+
+    let command = cli
+        .command
+        .unwrap_or_else(|| Command::Tui(TuiOptions::default()));
+    match (options.boards, &command) {
+        (2 | 4 | 8, _) if options.hard => {
+            return Err("hard mode isn't supported with more than one board".to_string())
+        }
+        (1, _) | (2 | 4 | 8, Command::Tui(_)) => {}
+        (2 | 4 | 8, _) => return Err("only the TUI supports more than one board".to_string()),
+        (other, _) => return Err(format!("boards must be 1, 2, 4 or 8 (got {})", other)),
+    }
+    let (words_list, answers_list) = match command {
+        Command::Tui(_)
+        | Command::Solve { .. }
+        | Command::Simulate { .. }
+        | Command::Play { .. }
+        | Command::Absurdle { .. }
+        | Command::Suggest { .. } => {
+            with_pattern_matrix(words_list, answers_list, &analyzers, &options)?
+        }
+        _ => (words_list, answers_list),
+    };
+
+    match command {
+        Command::Tui(tui) => {
+            if options.boards > 1 {
+                let mut main_window: MainWindow<N> = MainWindow::init(rows, false);
+                main_window.run_multi_board_loop(
+                    words_list,
+                    answers_list,
+                    analyzers,
+                    options.boards,
+                )?;
+            } else {
+                let session_path = tui.session.or_else(|| session::default_path::<N>(rows));
+                let resume = match &session_path {
+                    Some(path) if !tui.new => choose_session(path, tui.resume)?,
+                    _ => None,
+                };
+                let game = GameOptions {
+                    session: session_path,
+                    resume,
+                    ..GameOptions::helper(options.history_path())
+                };
+                let mut main_window: MainWindow<N> = MainWindow::init(rows, options.hard);
+                main_window.run_main_loop(words_list, answers_list, analyzers, game)?;
+            }
+        }
+        Command::Solve {
+            answers,
+            run,
+            format,
+        } => {
+            let results = auto_guess(
+                answers,
+                words_list,
+                answers_list,
+                analyzers,
+                rows,
+                options.hard,
+                &run,
+            )?;
+            print_results(results, rows, format)?;
+        }
+        Command::Simulate { games, run, format } => {
+            let answers = answers_list
+                .words()
+                .take(games.unwrap_or(usize::MAX))
+                .map(|wf| wf.word.clone())
+                .collect();
+            let results = auto_guess(
+                answers,
+                words_list,
+                answers_list,
+                analyzers,
+                rows,
+                options.hard,
+                &run,
+            )?;
+            print_results(results, rows, format)?;
+        }
+        Command::Play {
+            answers,
+            seed,
+            daily,
+            hints,
+        } => {
+            let seed = match seed {
+                Some(seed) => seed,
+                None if daily => daily_seed(),
+                None => rand::random(),
+            };
+            let game = GameOptions {
+                host: Some(Box::new(FixedAnswer::random(&answers_list, answers, seed)?)),
+                mode: GameMode::Play,
+                hints: hints.hints,
+                history: options.history_path(),
+                session: None,
+                resume: None,
+            };
+            let mut main_window: MainWindow<N> = MainWindow::init(rows, options.hard);
+            main_window.run_main_loop(words_list, answers_list, analyzers, game)?;
+        }
+        Command::Absurdle {
+            auto,
+            hints,
+            run,
+            format,
+        } => {
+            if auto {
+                let auto_guesser: AutoGuesser<N> = AutoGuesser {
+                    answer_words: Vec::new(),
+                    guesses_list: words_list,
+                    answers_list,
+                    analyzers,
+                    rows,
+                    hard_mode: options.hard,
+                    threads: run.threads,
+                };
+                print_results(auto_guesser.guess_adversarial()?, rows, format)?;
+            } else {
+                let game = GameOptions {
+                    host: Some(Box::new(Absurdle::new(&answers_list)?)),
+                    mode: GameMode::Absurdle,
+                    hints: hints.hints,
+                    history: options.history_path(),
+                    session: None,
+                    resume: None,
+                };
+                let mut main_window: MainWindow<N> = MainWindow::init(rows, options.hard);
+                main_window.run_main_loop(words_list, answers_list, analyzers, game)?;
+            }
+        }
+        Command::Suggest { guesses, top } => {
+            let grid: GuessGrid<N> = parse_grid(&guesses, rows)?;
+            print_suggestions(
+                &words_list,
+                &answers_list,
+                &grid,
+                &analyzers,
+                options.hard,
+                top,
+            )?
+        }
+        Command::Explain { word, guesses } => {
+            let grid: GuessGrid<N> = parse_grid(&guesses, rows)?;
+            println!("{}", explain(&words_list, &grid, &word)?);
+        }
+        Command::Share { guesses, answer } => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("couldn't read share text: {}", e))?;
+            let share: ShareText<N> = ShareText::parse(&text)?;
+            let grid: GuessGrid<N> = match answer {
+                Some(answer) => share.to_grid_with_answer(&answer, &words_list, rows)?,
+                None => share.to_grid_with_guesses(&guesses, rows)?,
+            };
+            for row in grid.rows().take(share.rows.len()) {
+                println!("{}:{}", row.word(), row.feedback());
             }
-            for analyzer_result in result.analyzer_results {
-                println!(
-                    "    {}: {} in {}",
-                    analyzer_result.name,
-                    analyzer_result.result,
-                    analyzer_result.guesses.len()
-                );
-                for row in analyzer_result.guesses {
-                    print!("      ");
-                    for guess_ch in row.chars() {
-                        let display = match guess_ch.knowledge() {
-                            CharKnowledge::Unknown => "⤵️",
-                            CharKnowledge::WrongPosition => "🟨",
-                            CharKnowledge::Correct => "🟩",
-                            CharKnowledge::Missing => "⬛️",
-                        };
-                        print!("{}", display)
-                    }
+        }
+        Command::Wordlist => {
+            for word_freq in words_list.words() {
+                println!("{}\t{}", word_freq.word, word_freq.freq);
+            }
+        }
+        Command::Stats => {
+            let path = options
+                .history_path()
+                .ok_or("there's no history file; pass --history to pick one")?;
+            let by_mode = Stats::by_mode(&history::load(&path)?);
+            if by_mode.is_empty() {
+                println!("no games in {} yet", path.display());
+            }
+            for (idx, (mode, stats)) in by_mode.iter().enumerate() {
+                if idx > 0 {
                     println!();
                 }
+                println!("{} games:", mode);
+                print_stats(stats, rows);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The session to resume, if any. With `--resume`, that's the last session, which must exist;
+/// otherwise, if there's an unfinished puzzle, we ask whether to pick it up. We can only ask if
+/// stdin is a terminal, and an unreadable session just means starting a new puzzle.
+fn choose_session<const N: usize>(path: &Path, resume: bool) -> Result<Option<Session<N>>, String> {
+    if resume {
+        return match session::load(path)? {
+            Some(session) => Ok(Some(session)),
+            None => Err(format!(
+                "there's no session to resume in {}",
+                path.display()
+            )),
+        };
+    }
+    let Ok(Some(session)) = session::load::<N>(path) else {
+        return Ok(None);
+    };
+    if session.is_empty() || !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    print!(
+        "Resume your unfinished puzzle ({} {} so far)? [Y/n] ",
+        session.active_row,
+        if session.active_row == 1 {
+            "guess"
+        } else {
+            "guesses"
+        }
+    );
+    io::stdout()
+        .flush()
+        .map_err(|e| format!("couldn't ask about the last session: {}", e))?;
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| format!("couldn't ask about the last session: {}", e))?;
+    let answer = answer.trim();
+    if answer.is_empty() || answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") {
+        Ok(Some(session))
+    } else {
+        Ok(None)
+    }
+}
+
+fn load_words<const N: usize>(options: &CommonOptions) -> Result<WordList<N>, String> {
+    let mut config = match &options.words_config {
+        Some(path) => WordListConfig::from_file(path)?,
+        None => WordListConfig::default(),
+    };
+    if !options.words.is_empty() {
+        let embedded = options.words.iter().flat_map(|e| e.0.clone()).collect();
+        config.set_embedded(embedded);
+    }
+    config
+        .sources
+        .extend(options.words_file.iter().cloned().map(SourceConfig::from));
+    if let Some(limit) = options.words_limit {
+        config.limit = limit;
+    }
+    if let Some(normalize) = options.normalize {
+        config.normalize = normalize;
+    }
+    config.load()
+}
+
+/// The allowed guesses and the possible answers. Without --answers-file, any word in the word
+/// list can be the answer. With it, the answers come from that file, and any of them that aren't
+/// in the word list are added to the end of it, so that they can be guessed.
+fn load_answers<const N: usize>(
+    words_list: WordList<N>,
+    options: &CommonOptions,
+) -> Result<(WordList<N>, WordList<N>), String> {
+    let Some(spec) = &options.answers_file else {
+        return Ok((words_list.clone(), words_list));
+    };
+    if spec.weight != 1.0 {
+        return Err(format!(
+            "--answers-file doesn't take a weight (got {})",
+            spec.weight
+        ));
+    }
+    let answers_list: WordList<N> = load_words_file(&spec.path, spec.format)?;
+    if answers_list.is_empty() {
+        return Err(format!(
+            "there are no {}-letter words in {}",
+            N,
+            spec.path.display()
+        ));
+    }
+    let known: HashSet<&str> = words_list.words().map(|wf| wf.word.as_str()).collect();
+    let missing: Vec<WordFreq> = answers_list
+        .words()
+        .filter(|wf| !known.contains(wf.word.as_str()))
+        .map(|wf| WordFreq {
+            word: wf.word.clone(),
+            freq: 0.0,
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok((words_list, answers_list));
+    }
+    let guesses = words_list.words().cloned().chain(missing).collect();
+    Ok((WordList::new(guesses), answers_list))
+}
+
+/// Attaches a pattern matrix to the word lists, for the commands that run the analyzers. The
+/// answers are all in the guesses list, so one matrix covers both. If none of the analyzers use
+/// the matrix, or it would be too big, the lists are left as they are, and any patterns that are
+/// needed are computed as they're needed.
+fn with_pattern_matrix<const N: usize>(
+    guesses_list: WordList<N>,
+    answers_list: WordList<N>,
+    analyzers: &[Box<dyn Analyzer<N>>],
+    options: &CommonOptions,
+) -> Result<(WordList<N>, WordList<N>), String> {
+    if !analyzers
+        .iter()
+        .any(|analyzer| analyzer.uses_pattern_matrix())
+    {
+        return Ok((guesses_list, answers_list));
+    }
+    if !PatternMatrix::<N>::fits(guesses_list.len()) {
+        eprintln!(
+            "warning: {} words are too many to precompute their patterns (that would take more \
+            than {} MiB), so they'll be computed as they're needed",
+            guesses_list.len(),
+            PatternMatrix::<N>::MAX_BYTES / (1024 * 1024)
+        );
+        return Ok((guesses_list, answers_list));
+    }
+    let matrix = Arc::new(PatternMatrix::load_or_build(
+        &guesses_list,
+        options.pattern_cache.as_deref(),
+    )?);
+    Ok((
+        guesses_list.with_pattern_matrix(matrix.clone()),
+        answers_list.with_pattern_matrix(matrix),
+    ))
+}
+
+fn auto_guess<const N: usize>(
+    answers: Vec<String>,
+    words_list: WordList<N>,
+    answers_list: WordList<N>,
+    analyzers: Vec<Box<dyn Analyzer<N>>>,
+    rows: usize,
+    hard_mode: bool,
+    run: &RunOptions,
+) -> Result<Vec<ResultsByWord<N>>, String> {
+    let auto_guesser: AutoGuesser<N> = AutoGuesser {
+        answer_words: answers,
+        guesses_list: words_list,
+        answers_list,
+        analyzers,
+        rows,
+        hard_mode,
+        threads: run.threads,
+    };
+    auto_guesser.guess_all()
+}
+
+fn print_results<const N: usize>(
+    results: Vec<ResultsByWord<N>>,
+    rows: usize,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let mut stdout = io::stdout().lock();
+    match format {
+        OutputFormat::Text => print_games(results),
+        OutputFormat::Share => print_share_texts(results, rows),
+        OutputFormat::Summary => print_summary(&results, rows),
+        OutputFormat::Jsonl => results_output::write_json_lines(&results, &mut stdout)
+            .map_err(|e| format!("couldn't write results: {}", e))?,
+        OutputFormat::Csv => results_output::write_csv(&results, &mut stdout)
+            .map_err(|e| format!("couldn't write results: {}", e))?,
+    }
+    Ok(())
+}
+
+fn parse_grid<const N: usize>(
+    guesses: &[String],
+    row_count: usize,
+) -> Result<GuessGrid<N>, String> {
+    let rows = guesses
+        .iter()
+        .map(|g| g.parse())
+        .collect::<Result<Vec<GuessStr<N>>, String>>()?;
+    GuessGrid::from_rows(rows, row_count)
+}
+
+fn explain<const N: usize>(
+    words_list: &WordList<N>,
+    grid: &GuessGrid<N>,
+    word: &str,
+) -> Result<String, String> {
+    let word = word.to_ascii_uppercase();
+    if word.chars().count() != N {
+        return Err(format!(
+            "expected a {}-letter word, but got \"{}\"",
+            N, word
+        ));
+    }
+    let in_list = words_list.words().any(|wf| wf.word == word);
+    let explanation = match KnownWordConstraints::explain(grid, &word) {
+        Some(violation) => format!("{} was ruled out: {}", word, violation),
+        None => format!("{} is still possible", word),
+    };
+    Ok(if in_list {
+        explanation
+    } else {
+        format!("{} (but it isn't in the word list)", explanation)
+    })
+}
+
+fn print_suggestions<const N: usize>(
+    words_list: &WordList<N>,
+    answers_list: &WordList<N>,
+    grid: &GuessGrid<N>,
+    analyzers: &[Box<dyn Analyzer<N>>],
+    hard_mode: bool,
+    top: usize,
+) -> Result<(), String> {
+    let constraints = KnownWordConstraints::from_grid_checked(grid).map_err(|contradictions| {
+        let reasons: Vec<String> = contradictions.iter().map(|c| c.to_string()).collect();
+        format!("the guesses contradict each other: {}", reasons.join("; "))
+    })?;
+    let possible_words = answers_list.filter_preview(&constraints);
+    let allowed_guesses = if hard_mode {
+        words_list.filter_preview(&constraints.hard_mode())
+    } else {
+        words_list.clone()
+    };
+
+    println!("{} possible answers", possible_words.len());
+    if possible_words.len() <= top {
+        for word_freq in possible_words.words() {
+            println!("    {}", word_freq.word);
+        }
+    }
+    println!();
+
+    for analyzer in analyzers {
+        let mut scored = analyzer.analyze(&allowed_guesses, &possible_words);
+        scored.sort();
+        ScoredWord::normalize_scores(&mut scored);
+        println!("{}:", analyzer.name());
+        for scored_word in scored.iter().take(top) {
+            println!("    {}: {:.3}", scored_word.word, scored_word.score);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+fn print_games<const N: usize>(results: Vec<ResultsByWord<N>>) {
+    for result in results {
+        println!("{}:", result.answer);
+        for analyzer_result in result.analyzer_results {
+            println!(
+                "    {}: {} in {}",
+                analyzer_result.name,
+                analyzer_result.result,
+                analyzer_result.guesses.len()
+            );
+            for row in analyzer_result.guesses {
+                print!("      ");
+                for guess_ch in row.chars() {
+                    print!("{}", guess_ch.knowledge().emoji())
+                }
                 println!();
             }
+            println!();
+        }
+    }
+}
+
+fn print_share_texts<const N: usize>(results: Vec<ResultsByWord<N>>, rows: usize) {
+    for result in results {
+        for analyzer_result in result.analyzer_results {
+            let title = format!("{} ({})", result.answer, analyzer_result.name);
+            let share = ShareText::from_rows(&title, &analyzer_result.guesses, rows, false);
+            println!("{}", share);
+        }
+    }
+}
+
+fn print_stats(stats: &Stats, rows: usize) {
+    // With no games at all, everything is 0%.
+    let percent = |count: usize, total: usize| count as f64 * 100.0 / total.max(1) as f64;
+    println!(
+        "played {}, won {} ({:.1}%)",
+        stats.games,
+        stats.wins,
+        percent(stats.wins, stats.games)
+    );
+    println!(
+        "current streak {}, best streak {}",
+        stats.current_streak, stats.max_streak
+    );
+    println!();
+    println!("guess distribution:");
+    let mut distribution = stats.distribution.clone();
+    distribution.resize(distribution.len().max(rows), 0);
+    let max_count = distribution.iter().copied().max().unwrap_or(0);
+    for (idx, count) in distribution.iter().enumerate() {
+        let bar_len = (count * 40).checked_div(max_count).unwrap_or(0);
+        println!("    {}: {:>6} {}", idx + 1, count, "█".repeat(bar_len));
+    }
+    if !stats.analyzers.is_empty() {
+        println!();
+        println!("guesses that were each analyzer's top pick:");
+        for analyzer in &stats.analyzers {
+            println!(
+                "    {}: {}/{} ({:.1}%)",
+                analyzer.name,
+                analyzer.matched,
+                analyzer.guesses,
+                percent(analyzer.matched, analyzer.guesses)
+            );
+        }
+    }
+}
+
+fn print_summary<const N: usize>(results: &[ResultsByWord<N>], rows: usize) {
+    let summaries = strategy_stats::summarize(results);
+    let games = summaries.iter().map(|s| s.games).max().unwrap_or(0);
+    println!("{} games per analyzer", games);
+    println!();
+    for summary in summaries {
+        let fmt_opt = |value: Option<f64>| match value {
+            Some(value) => format!("{:.3}", value),
+            None => "-".to_string(),
+        };
+        println!("{}:", summary.name);
+        println!(
+            "    won {}/{} ({:.1}%), mean {}, median {}",
+            summary.wins(),
+            summary.games,
+            summary.win_rate() * 100.0,
+            fmt_opt(summary.mean_guesses()),
+            fmt_opt(summary.median_guesses()),
+        );
+        // Show every row, even if no games were won in that many guesses.
+        let mut histogram = summary.histogram.clone();
+        histogram.resize(histogram.len().max(rows), 0);
+        let max_count = histogram.iter().copied().max().unwrap_or(0);
+        for (idx, count) in histogram.iter().enumerate() {
+            // Scale the bars so that the longest one is 40 chars.
+            let bar_len = (count * 40).checked_div(max_count).unwrap_or(0);
+            println!("    {}: {:>6} {}", idx + 1, count, "█".repeat(bar_len));
+        }
+        if !summary.failures.is_empty() {
+            println!(
+                "    failed ({}): {}",
+                summary.failures.len(),
+                summary.failures.join(", ")
+            );
         }
+        println!();
     }
 }
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::guess::guesses::GuessStr;
+use crate::util::data_dir;
+
+/// An unfinished puzzle in the interactive helper: everything that's been entered in the grid, and
+/// where the cursor was. The TUI saves one of these after every change, so that the puzzle can be
+/// picked up again if the terminal closes.
+#[derive(Serialize, Deserialize)]
+pub struct Session<const N: usize> {
+    /// The rows up to and including the active one; the rows after it are always empty.
+    pub rows: Vec<GuessStr<N>>,
+    pub active_row: usize,
+    pub active_col: usize,
+}
+
+impl<const N: usize> Session<N> {
+    /// Whether nothing has been entered at all, so there's nothing worth resuming.
+    pub fn is_empty(&self) -> bool {
+        self.rows
+            .iter()
+            .all(|row| row.chars().all(|ch| ch.ch().is_none()))
+    }
+}
+
+/// Where the session goes if `--session` isn't given: `session-NxR.json`, in the `data_dir`, where
+/// `N` is the word length and `R` is `rows`. Each size of puzzle gets its own file, so that
+/// starting a different size doesn't replace an unfinished one.
+pub fn default_path<const N: usize>(rows: usize) -> Option<PathBuf> {
+    Some(data_dir()?.join(format!("session-{}x{}.json", N, rows)))
+}
+
+/// Reads the session file, or `None` if there isn't one. Fails if it's for a different word length.
+pub fn load<const N: usize>(path: &Path) -> Result<Option<Session<N>>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("invalid session {}: {}", path.display(), e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("couldn't read {}: {}", path.display(), e)),
+    }
+}
+
+/// Replaces the session file with `session`. The new file is written alongside the old one and
+/// then moved over it, so that a crash partway through doesn't lose both.
+pub fn save<const N: usize>(path: &Path, session: &Session<N>) -> Result<(), String> {
+    let err = |e: std::io::Error| format!("couldn't save session to {}: {}", path.display(), e);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(err)?;
+    }
+    let json = serde_json::to_string(session).expect("sessions always serialize");
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, json).map_err(err)?;
+    fs::rename(&tmp_path, path).map_err(err)
+}
+
+/// Removes the session file, if there is one: the puzzle is over, so there's nothing to resume.
+pub fn clear(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            Err(format!("couldn't remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::guess::known_word_constraints::CharKnowledge;
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("wordlehelper-session-{}", std::process::id()));
+        let path = dir.join("session.json");
+        assert!(load::<5>(&path).unwrap().is_none());
+
+        let mut active: GuessStr<5> = GuessStr::new();
+        active.guess_mut(0).set_ch('S');
+        active.guess_mut(0).set_knowledge(CharKnowledge::Missing);
+        let session = Session {
+            rows: vec!["CRANE:BYGBB".parse().unwrap(), active],
+            active_row: 1,
+            active_col: 1,
+        };
+        assert!(!session.is_empty());
+        save(&path, &session).unwrap();
+
+        let loaded: Session<5> = load(&path).unwrap().unwrap();
+        let rows: Vec<(String, String)> = loaded
+            .rows
+            .iter()
+            .map(|row| (row.word(), row.feedback()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("CRANE".to_string(), "BYGBB".to_string()),
+                ("S    ".to_string(), "B....".to_string())
+            ],
+            rows
+        );
+        assert_eq!((1, 1), (loaded.active_row, loaded.active_col));
+        // A session for 5-letter words doesn't fit any other length.
+        assert!(load::<6>(&path).is_err());
+
+        clear(&path).unwrap();
+        assert!(load::<5>(&path).unwrap().is_none());
+        clear(&path).unwrap();
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn default_paths() {
+        // Without $HOME or $XDG_DATA_HOME, there's no default at all.
+        let Some(path) = default_path::<5>(6) else {
+            return;
+        };
+        assert_eq!("session-5x6.json", path.file_name().unwrap());
+        assert_eq!(
+            Some(path.with_file_name("session-6x7.json")),
+            default_path::<6>(7)
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let session: Session<5> = Session {
+            rows: vec![GuessStr::new()],
+            active_row: 0,
+            active_col: 0,
+        };
+        assert!(session.is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::analyze::analyzer::{Analyzer, ScoredWord};
+use crate::analyze::multi_board::analyze_boards;
+use crate::guess::known_word_constraints::Contradiction;
 use crate::ui::text_scroll_pane::TextScroll;
 use crate::ui::widget::Widget;
 use crate::util::{incr_usize, WRAP};
@@ -12,7 +14,19 @@ pub struct AnalyzersUI<const N: usize> {
     output: TextScroll,
     analyzers: Vec<Box<dyn Analyzer<N>>>,
     active_analyzer: usize,
-    previous_words: Rc<WordList<N>>,
+    previous_guesses: Rc<WordList<N>>,
+    previous_answers: Answers<N>,
+    /// If the guesses contradict each other, we show these instead of any analysis.
+    contradictions: Vec<String>,
+    /// When playing a game, the suggestions are hints, and the user may not want to see them.
+    hidden: bool,
+}
+
+/// The possible answers from the last analysis.
+enum Answers<const N: usize> {
+    Single(Rc<WordList<N>>),
+    /// For a multi-board game: each unsolved board's possible answers.
+    Boards(Rc<Vec<WordList<N>>>),
 }
 
 impl<const N: usize> AnalyzersUI<N> {
@@ -21,23 +35,76 @@ impl<const N: usize> AnalyzersUI<N> {
             output,
             analyzers,
             active_analyzer: 0,
-            previous_words: Rc::new(WordList::empty()),
+            previous_guesses: Rc::new(WordList::empty()),
+            previous_answers: Answers::Single(Rc::new(WordList::empty())),
+            contradictions: Vec::new(),
+            hidden: false,
         }
     }
 
-    pub fn analyze(&mut self, word_list: Rc<WordList<N>>) {
-        self.previous_words = word_list;
+    pub fn analyze(&mut self, guesses_list: Rc<WordList<N>>, word_list: Rc<WordList<N>>) {
+        self.previous_guesses = guesses_list;
+        self.previous_answers = Answers::Single(word_list);
+        self.contradictions.clear();
+        self.redraw();
+    }
+
+    /// Like `analyze`, but for a multi-board game: each of `boards` is one unsolved board's
+    /// possible answers. See `analyze_boards`.
+    pub fn analyze_boards(&mut self, guesses_list: Rc<WordList<N>>, boards: Rc<Vec<WordList<N>>>) {
+        self.previous_guesses = guesses_list;
+        self.previous_answers = Answers::Boards(boards);
+        self.contradictions.clear();
+        self.redraw();
+    }
+
+    pub fn show_contradictions(&mut self, contradictions: &[Contradiction]) {
+        self.show_contradiction_texts(contradictions.iter().map(|c| c.to_string()).collect());
+    }
+
+    pub fn show_contradiction_texts(&mut self, contradictions: Vec<String>) {
+        self.contradictions = contradictions;
+        self.redraw();
+    }
+
+    pub fn analyzers(&self) -> &[Box<dyn Analyzer<N>>] {
+        &self.analyzers
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Hides or shows the suggestions. While they're hidden, the analyzers don't run at all.
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
         self.redraw();
     }
 }
 
-impl<'a, const N: usize> AnalyzersUI<N> {
+impl<const N: usize> AnalyzersUI<N> {
     fn redraw(&mut self) {
+        if self.hidden {
+            self.output.set_title("Hints");
+            self.output
+                .set_texts(vec!["Hidden; ctrl-g shows them".to_string()]);
+            return;
+        }
+        if !self.contradictions.is_empty() {
+            self.output.set_title("Contradictions");
+            self.output.set_texts(self.contradictions.clone());
+            return;
+        }
         let Some(analyzer) = self.analyzers.get(self.active_analyzer) else {
-            return
+            return;
         };
         self.output.set_title(&analyzer.name());
-        let mut scored = analyzer.analyze(self.previous_words.deref());
+        let mut scored = match &self.previous_answers {
+            Answers::Single(words) => analyzer.analyze(self.previous_guesses.deref(), words),
+            Answers::Boards(boards) => {
+                analyze_boards(analyzer.as_ref(), self.previous_guesses.deref(), boards)
+            }
+        };
         scored.sort();
         ScoredWord::normalize_scores(&mut scored);
         let texts: Vec<String> = scored
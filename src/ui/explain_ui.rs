@@ -0,0 +1,125 @@
+use crate::guess::guesses::GuessGrid;
+use crate::guess::known_word_constraints::KnownWordConstraints;
+use crate::ui::widget::Widget;
+use crate::ui::window_helper::{Color, WindowState};
+use crate::word_list::WordList;
+use pancurses::{Input, Window};
+
+/// A prompt for asking why a word was ruled out. While it's active, it takes all letter input; press
+/// enter to explain the word, or escape to go back to the guesses.
+pub struct ExplainUI<const N: usize> {
+    window: Window,
+    active: bool,
+    word: String,
+    message: String,
+}
+
+impl<const N: usize> ExplainUI<N> {
+    /// One line for the prompt, and up to three for the explanation.
+    pub const HEIGHT: i32 = 4;
+
+    pub fn new(owner: &Window, pos_y: i32, pos_x: i32, cols: i32) -> Self {
+        let res = ExplainUI {
+            window: owner
+                .subwin(Self::HEIGHT, cols, pos_y, pos_x)
+                .expect("couldn't create explain widget"),
+            active: false,
+            word: String::new(),
+            message: String::new(),
+        };
+        res.redraw();
+        res
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Explains the word that's been entered so far, given the guesses in `grid`.
+    pub fn explain(&mut self, grid: &GuessGrid<N>, words_list: &WordList<N>) {
+        self.message = if self.word.len() != N {
+            format!("Enter a {}-letter word.", N)
+        } else if let Some(violation) = KnownWordConstraints::explain(grid, &self.word) {
+            format!("Ruled out: {}.", violation)
+        } else if words_list.words().any(|wf| wf.word == self.word) {
+            "Still possible.".to_string()
+        } else {
+            "Not ruled out, but it isn't in the word list.".to_string()
+        };
+        self.redraw();
+    }
+}
+
+impl<const N: usize> Widget for ExplainUI<N> {
+    fn title(&self) -> Option<String> {
+        None
+    }
+
+    fn set_active(&mut self, active: bool) {
+        self.active = active;
+        if active {
+            self.word.clear();
+            self.message.clear();
+        }
+        self.redraw();
+    }
+
+    fn handle_input(&mut self, input: Input) -> Option<Input> {
+        match input {
+            Input::Character('\x1B') => self.set_active(false), // escape
+            Input::Character('\x7F') => {
+                // delete
+                self.word.pop();
+                self.redraw();
+            }
+            Input::Character(ch) if ch.is_ascii_alphabetic() => {
+                if self.word.len() < N {
+                    self.word.push(ch.to_ascii_uppercase());
+                }
+                self.redraw();
+            }
+            _ => return Some(input),
+        }
+        None
+    }
+}
+
+impl<const N: usize> ExplainUI<N> {
+    fn redraw(&self) {
+        self.window.erase();
+        if !self.active {
+            _ = self
+                .window
+                .mvaddstr(0, 0, "ctrl-w: why was a word ruled out?");
+            return;
+        }
+        _ = self
+            .window
+            .mvaddstr(0, 0, format!("Why not: {}", self.word));
+        let window_state = WindowState::new(&self.window);
+        window_state.set_color(Color::Warning);
+        let width = self.window.get_max_x().max(1) as usize;
+        for (idx, line) in wrap(&self.message, width).iter().take(3).enumerate() {
+            _ = self.window.mvaddstr(idx as i32 + 1, 0, line);
+        }
+    }
+}
+
+/// Splits `text` into lines of at most `width` chars, at spaces where possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -1,5 +1,9 @@
 use crate::guess::guesses::{GuessChar, GuessGrid};
-use crate::guess::known_word_constraints::{CharKnowledge, KnownWordConstraints};
+use crate::guess::host::Host;
+use crate::guess::known_word_constraints::{
+    CharKnowledge, Contradiction, GridCell, KnownWordConstraints,
+};
+use crate::session::Session;
 use crate::ui::widget::Widget;
 use crate::ui::window_helper::{Color, WindowState};
 use crate::util::{incr_usize, WRAP};
@@ -12,47 +16,184 @@ use std::thread;
 use std::time::Duration;
 use strum::EnumCount;
 
-pub struct GuessesUI<const N: usize, const R: usize> {
+pub struct GuessesUI<const N: usize> {
     window: Window,
-    grid: GuessGrid<N, R>,
+    grid: GuessGrid<N>,
     active_row: usize,
     active_col: usize,
     has_new_knowledge: Cell<bool>,
+    guesses_list: WordList<N>,
     possible_words: WordList<N>,
     current_row_inference: [Option<char>; N],
+    hard_mode: bool,
+    /// If there's a host, we're playing against it: the user just types each guess, and the host
+    /// fills in the feedback. Otherwise, the user enters the feedback too.
+    host: Option<Box<dyn Host<N>>>,
+    /// Shown below the grid, instead of the hard mode marker: how a hosted game ended, or why a
+    /// guess wasn't accepted.
+    status: String,
+    game_over: bool,
 }
 
-impl<const N: usize, const R: usize> GuessesUI<N, R> {
-    pub fn new(window: &Window, pos_y: i32, pos_x: i32) -> Self {
+impl<const N: usize> GuessesUI<N> {
+    /// Four columns per letter, plus two for the active row's marker.
+    pub const WIDTH: i32 = 4 * N as i32 + 2;
+
+    /// How much room the widget takes for `row_count` rows: three lines per row, plus one for the
+    /// hard mode marker (or the status).
+    pub fn height(row_count: usize) -> i32 {
+        3 * row_count as i32 + 1
+    }
+
+    /// A grid of `row_count` rows, not in hard mode; see `set_hard_mode`.
+    pub fn new(
+        window: &Window,
+        pos_y: i32,
+        pos_x: i32,
+        row_count: usize,
+        guesses_list: WordList<N>,
+        answers_list: WordList<N>,
+        host: Option<Box<dyn Host<N>>>,
+    ) -> Self {
         let res = Self {
             window: window
-                .subwin((R * 4) as i32, (N * 4 + 2) as i32, pos_y, pos_x)
+                .subwin(Self::height(row_count), Self::WIDTH, pos_y, pos_x)
                 .expect("couldn't create entry widget"),
-            grid: GuessGrid::new(),
+            grid: GuessGrid::new(row_count),
             active_row: 0,
             active_col: 0,
             has_new_knowledge: Cell::new(true),
-            possible_words: WordList::std(),
+            guesses_list,
+            possible_words: answers_list,
             current_row_inference: [None; N],
+            hard_mode: false,
+            host,
+            status: String::new(),
+            game_over: false,
         };
         res.draw_guess_grid();
+        res.draw_status();
         res
     }
 
+    /// If anything has changed since the last invocation, passes the allowed guesses and the
+    /// possible answers to `handler`. If the grid contradicts itself, there aren't any meaningful
+    /// words to pass; `handler` gets the contradictions instead.
     pub fn handle_new_knowledge<F>(&self, mut handler: F)
     where
-        F: FnMut(Rc<WordList<N>>),
+        F: FnMut(Result<(Rc<WordList<N>>, Rc<WordList<N>>), Vec<Contradiction>>),
     {
         if self.has_new_knowledge.get() {
-            let constraints = KnownWordConstraints::from_grid(&self.grid);
-            let possible_words = self.possible_words.filter_preview(&constraints);
-            handler(Rc::new(possible_words));
+            let knowledge =
+                KnownWordConstraints::from_grid_checked(&self.grid).map(|constraints| {
+                    let possible_words = self.possible_words.filter_preview(&constraints);
+                    let allowed_guesses = if self.hard_mode {
+                        self.guesses_list.filter_preview(&constraints.hard_mode())
+                    } else {
+                        self.guesses_list.clone()
+                    };
+                    (Rc::new(allowed_guesses), Rc::new(possible_words))
+                });
+            handler(knowledge);
             self.has_new_knowledge.set(false);
         }
     }
+
+    pub fn grid(&self) -> &GuessGrid<N> {
+        &self.grid
+    }
+
+    /// The possible answers at the start of the active row: what the rows before it allow, not
+    /// counting anything entered in it yet.
+    pub fn possible_words_at_row_start(&self) -> &WordList<N> {
+        &self.possible_words
+    }
+
+    /// Whether the game is over: the user pressed enter on a row that solved it, or on the last
+    /// row.
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.game_over && self.fully_guessed()
+    }
+
+    /// The answer, if it's known: a host always knows it, but otherwise we only know it once it's
+    /// been guessed.
+    pub fn answer(&self) -> Option<String> {
+        match &self.host {
+            Some(host) => Some(host.answer().to_string()),
+            None if self.is_won() => Some(self.grid.guesses()[self.active_row].word()),
+            None => None,
+        }
+    }
+
+    /// Everything that's been entered so far, for saving to a session file.
+    pub fn session(&self) -> Session<N> {
+        Session {
+            rows: self
+                .grid
+                .rows()
+                .take(self.active_row + 1)
+                .cloned()
+                .collect(),
+            active_row: self.active_row,
+            active_col: self.active_col,
+        }
+    }
+
+    /// Picks up where `session` left off. This is for a new grid, before anything's been entered
+    /// in it. Fails if the session doesn't fit in this grid.
+    pub fn restore(&mut self, session: Session<N>) -> Result<(), String> {
+        let Session {
+            mut rows,
+            active_row,
+            active_col,
+        } = session;
+        let row_count = self.grid.row_count();
+        if active_row >= row_count || active_col >= N || rows.len() > active_row + 1 {
+            return Err(format!(
+                "the session doesn't fit in {} rows of {}-letter words",
+                row_count, N
+            ));
+        }
+        let active = if rows.len() > active_row {
+            rows.pop()
+        } else {
+            None
+        };
+        // Get to the state that next_row would have left us in at the start of the active row,
+        // and then fill in what was entered in it.
+        self.grid = GuessGrid::from_rows(rows, row_count)?;
+        self.possible_words
+            .filter(&KnownWordConstraints::from_grid(&self.grid));
+        self.current_row_inference = self.grid.known_chars();
+        if let Some(active) = active {
+            *self.grid.guess_mut(active_row) = active;
+        }
+
+        let window_state = WindowState::new(&self.window);
+        window_state.set_color(Color::Hidden);
+        self.draw_active_marker();
+        self.active_row = active_row;
+        self.active_col = active_col;
+        window_state.set_color(Color::StandardForeground);
+        self.draw_active_marker();
+
+        self.has_new_knowledge.set(true);
+        self.draw_guess_grid();
+        Ok(())
+    }
+
+    pub fn set_hard_mode(&mut self, hard_mode: bool) {
+        self.hard_mode = hard_mode;
+        self.has_new_knowledge.set(true);
+        self.draw_status();
+    }
 }
 
-impl<'a, const N: usize, const R: usize> Widget for GuessesUI<N, R> {
+impl<const N: usize> Widget for GuessesUI<N> {
     fn title(&self) -> Option<String> {
         None
     }
@@ -69,7 +210,11 @@ impl<'a, const N: usize, const R: usize> Widget for GuessesUI<N, R> {
     }
 
     fn handle_input(&mut self, input: Input) -> Option<Input> {
+        if self.game_over {
+            return Some(input);
+        }
         match input {
+            Input::KeyUp | Input::KeyDown if self.host.is_some() => return Some(input),
             Input::KeyUp | Input::KeyDown => self.cycle_guess_knowledge(input == Input::KeyUp),
             Input::KeyRight | Input::KeyLeft => self.move_active_ch(input == Input::KeyRight),
             Input::Character('\n') => self.handle_newline(),
@@ -77,7 +222,6 @@ impl<'a, const N: usize, const R: usize> Widget for GuessesUI<N, R> {
             Input::Character(input_ch) if input_ch.is_ascii_alphabetic() => {
                 if !(self.set_active_ch(input_ch)) {
                     return Some(input);
-                } else {
                 }
             }
             _ => {
@@ -89,8 +233,13 @@ impl<'a, const N: usize, const R: usize> Widget for GuessesUI<N, R> {
     }
 }
 
-impl<const N: usize, const R: usize> GuessesUI<N, R> {
+impl<const N: usize> GuessesUI<N> {
     fn draw_guess_grid(&self) {
+        let contradicting_cells: Vec<GridCell> =
+            match KnownWordConstraints::from_grid_checked(&self.grid) {
+                Ok(_) => Vec::new(),
+                Err(contradictions) => contradictions.into_iter().flat_map(|c| c.cells).collect(),
+            };
         for (row_idx, guess_str) in self.grid.guesses().iter().enumerate() {
             self.window.mv(3 * (row_idx as i32), 3);
             let (orig_y, orig_x) = self.window.get_cur_yx();
@@ -98,6 +247,11 @@ impl<const N: usize, const R: usize> GuessesUI<N, R> {
                 self.window.mv(orig_y, orig_x + (ch_idx as i32 * 4));
                 let style = if self.active_row == row_idx && self.active_col == ch_idx {
                     STYLE_ACTIVE
+                } else if contradicting_cells.contains(&GridCell {
+                    row: row_idx,
+                    col: ch_idx,
+                }) {
+                    STYLE_CONTRADICTION
                 } else {
                     STYLE_INACTIVE
                 };
@@ -131,6 +285,23 @@ impl<const N: usize, const R: usize> GuessesUI<N, R> {
             .mvaddstr(window_state.orig_y + 2, window_state.orig_x, style.bot);
     }
 
+    fn draw_status(&self) {
+        let text = if !self.status.is_empty() {
+            self.status.as_str()
+        } else if self.hard_mode {
+            "HARD MODE"
+        } else {
+            ""
+        };
+        let width = N * 4 + 1;
+        let text: String = text.chars().take(width).collect();
+        self.window.mvaddstr(
+            3 * self.grid.row_count() as i32,
+            0,
+            format!("{:^width$}", text),
+        );
+    }
+
     fn draw_active_marker(&self) {
         self.window
             .mvaddstr(3 * (self.active_row as i32) + 1, 1, "➤");
@@ -143,49 +314,108 @@ impl<const N: usize, const R: usize> GuessesUI<N, R> {
     }
 
     fn handle_newline(&mut self) {
+        if self.host.is_some() {
+            return self.submit_to_host();
+        }
         let active_row = &self.grid.guesses()[self.active_row];
         if active_row
             .guesses()
             .iter()
             .any(|c| c.knowledge() == CharKnowledge::Unknown)
+            || KnownWordConstraints::from_grid_checked(&self.grid).is_err()
         {
             self.report_error();
-        } else if self.active_row + 1 > N {
-            self.report_error();
-        } else if !self.fully_guessed() {
-            let window_state = WindowState::new(&self.window);
-            // Hide the current active marker
-            window_state.set_color(Color::Hidden);
-            self.draw_active_marker();
-
-            // Paint the new active marker
-            self.active_row += 1;
-            window_state.set_color(Color::StandardForeground);
-            self.draw_active_marker();
+        } else if self.fully_guessed() {
+            self.game_over = true;
+            self.status = format!("Solved in {}!", self.active_row + 1);
+            self.draw_status();
+        } else if self.active_row + 1 >= self.grid.row_count() {
+            self.game_over = true;
+            self.status = "Out of guesses".to_string();
+            self.draw_status();
+        } else {
+            self.next_row();
+        }
+    }
 
-            // Reify the possible words
-            self.possible_words
-                .filter(&KnownWordConstraints::from_grid(&self.grid));
-
-            // Get the current inference, and enter it in
-            self.current_row_inference = self.grid.known_chars();
-            let active_row = self.grid.guess_mut(self.active_row);
-            for (idx, inferred) in self.current_row_inference.iter().enumerate() {
-                if let Some(ch) = inferred {
-                    let cell = active_row.guess_mut(idx);
-                    cell.set_ch(*ch);
-                    cell.set_knowledge(CharKnowledge::Correct);
-                }
+    /// Sends the active row's word to the host, and fills in the feedback it gives.
+    fn submit_to_host(&mut self) {
+        let Some(host) = self.host.as_mut() else {
+            return;
+        };
+        let word = self.grid.guesses()[self.active_row].word();
+        let scored = if word.contains(' ') {
+            Err(String::new())
+        } else if !self.guesses_list.words().any(|wf| wf.word == word) {
+            Err(format!("{} isn't a word", word))
+        } else if self.hard_mode
+            && !KnownWordConstraints::from_grid(&self.grid)
+                .hard_mode()
+                .is_word_possible(&word)
+        {
+            Err("Use all the hints".to_string())
+        } else {
+            host.score(&word)
+        };
+        let answer = host.answer().to_string();
+        let row = match scored {
+            Ok(row) => row,
+            Err(e) => {
+                self.status = e;
+                self.draw_status();
+                self.report_error();
+                return;
             }
+        };
+        *self.grid.guess_mut(self.active_row) = row;
+        self.has_new_knowledge.set(true);
+        self.status.clear();
+        if self.fully_guessed() {
+            self.game_over = true;
+            self.status = format!("Solved in {}!", self.active_row + 1);
+        } else if self.active_row + 1 >= self.grid.row_count() {
+            self.game_over = true;
+            self.status = format!("It was {}", answer);
+        } else {
+            self.next_row();
+        }
+        self.draw_status();
+    }
+
+    /// Moves on to the next row, filling in any letters that are already known.
+    fn next_row(&mut self) {
+        let window_state = WindowState::new(&self.window);
+        // Hide the current active marker
+        window_state.set_color(Color::Hidden);
+        self.draw_active_marker();
+
+        // Paint the new active marker
+        self.active_row += 1;
+        window_state.set_color(Color::StandardForeground);
+        self.draw_active_marker();
 
-            // Set the active char on the current row to the first unknown char
-            self.active_col = active_row
-                .chars()
-                .enumerate()
-                .find(|e| e.1.knowledge() != CharKnowledge::Correct)
-                .map(|e| e.0)
-                .unwrap_or(0);
+        // Reify the possible words
+        self.possible_words
+            .filter(&KnownWordConstraints::from_grid(&self.grid));
+
+        // Get the current inference, and enter it in
+        self.current_row_inference = self.grid.known_chars();
+        let active_row = self.grid.guess_mut(self.active_row);
+        for (idx, inferred) in self.current_row_inference.iter().enumerate() {
+            if let Some(ch) = inferred {
+                let cell = active_row.guess_mut(idx);
+                cell.set_ch(*ch);
+                cell.set_knowledge(CharKnowledge::Correct);
+            }
         }
+
+        // Set the active char on the current row to the first unknown char
+        self.active_col = active_row
+            .chars()
+            .enumerate()
+            .find(|e| e.1.knowledge() != CharKnowledge::Correct)
+            .map(|e| e.0)
+            .unwrap_or(0);
     }
 
     fn move_active_ch(&mut self, right: bool) {
@@ -199,8 +429,8 @@ impl<const N: usize, const R: usize> GuessesUI<N, R> {
         let curr_knowledge = guess_ch.knowledge();
         let mut next_idx = curr_knowledge as usize;
         incr_usize(&mut next_idx, CharKnowledge::COUNT, up, WRAP);
-        let next =
-            CharKnowledge::from_repr(next_idx).expect(&format!("out of range for {}", next_idx));
+        let next = CharKnowledge::from_repr(next_idx)
+            .unwrap_or_else(|| panic!("out of range for {}", next_idx));
         guess_ch.set_knowledge(next);
         self.has_new_knowledge.set(true);
     }
@@ -247,7 +477,7 @@ impl<const N: usize, const R: usize> GuessesUI<N, R> {
     }
 }
 
-fn color_for_knowledge(knowledge: CharKnowledge) -> Color {
+pub(super) fn color_for_knowledge(knowledge: CharKnowledge) -> Color {
     match knowledge {
         CharKnowledge::Unknown => Color::StandardForeground,
         CharKnowledge::WrongPosition => Color::Warning,
@@ -273,3 +503,10 @@ const STYLE_INACTIVE: BoxStyle = BoxStyle {
     vert: '│',
     bot: "╰─╯",
 };
+
+/// For cells whose feedback contradicts another cell's.
+const STYLE_CONTRADICTION: BoxStyle = BoxStyle {
+    top: "┏!┓",
+    vert: '┃',
+    bot: "┗━┛",
+};
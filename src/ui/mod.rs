@@ -1,5 +1,7 @@
 mod analyzers_ui;
+mod explain_ui;
 mod guesses_ui;
+mod multi_board_ui;
 mod text_scroll_pane;
 pub mod tui;
 mod widget;
@@ -0,0 +1,274 @@
+use crate::guess::known_word_constraints::CharKnowledge;
+use crate::guess::multi_board::{BoardState, MultiBoard};
+use crate::ui::guesses_ui::color_for_knowledge;
+use crate::ui::widget::Widget;
+use crate::ui::window_helper::{Color, WindowState};
+use crate::util::{incr_usize, WRAP};
+use crate::word_list::WordList;
+use pancurses::{Input, Window};
+use std::rc::Rc;
+use strum::EnumCount;
+
+/// The most boards to show side by side; any more go on another row of boards.
+const BOARDS_ACROSS: usize = 4;
+
+/// Entry for a multi-board game (see `MultiBoard`). Letters go into the current row of every
+/// unsolved board at once, since each guess is made on all of them; the up and down arrows set the
+/// feedback on the active board, and `[` and `]` switch between boards.
+pub struct MultiBoardUI<const N: usize> {
+    window: Window,
+    game: MultiBoard<N>,
+    answers_list: WordList<N>,
+    active_board: usize,
+    active_col: usize,
+    has_new_knowledge: bool,
+    /// Each board's header: how many words it could still be, or that it's solved.
+    headers: Vec<String>,
+    message: String,
+}
+
+impl<const N: usize> MultiBoardUI<N> {
+    /// Three columns per letter, plus one between boards.
+    const BOARD_WIDTH: i32 = 3 * N as i32 + 1;
+
+    /// A header line, then the rows, then a blank line.
+    fn board_height(row_count: usize) -> i32 {
+        row_count as i32 + 2
+    }
+
+    /// How much room the widget needs for `board_count` boards of `row_count` rows: the boards,
+    /// plus a status line.
+    pub fn size(board_count: usize, row_count: usize) -> (i32, i32) {
+        let across = board_count.clamp(1, BOARDS_ACROSS);
+        let down = board_count.div_ceil(BOARDS_ACROSS).max(1);
+        (
+            Self::board_height(row_count) * down as i32 + 1,
+            Self::BOARD_WIDTH * across as i32,
+        )
+    }
+
+    pub fn new(
+        owner: &Window,
+        pos_y: i32,
+        pos_x: i32,
+        board_count: usize,
+        row_count: usize,
+        answers_list: WordList<N>,
+    ) -> Self {
+        let (lines, cols) = Self::size(board_count, row_count);
+        let res = MultiBoardUI {
+            window: owner
+                .subwin(lines, cols, pos_y, pos_x)
+                .expect("couldn't create boards widget"),
+            game: MultiBoard::new(board_count, row_count),
+            answers_list,
+            active_board: 0,
+            active_col: 0,
+            has_new_knowledge: true,
+            headers: vec![String::new(); board_count],
+            message: String::new(),
+        };
+        res.redraw();
+        res
+    }
+
+    /// If anything has changed since the last invocation, passes each unsolved board's possible
+    /// answers to `handler`. If any board's feedback contradicts itself, `handler` gets a
+    /// description of each contradiction instead.
+    pub fn handle_new_knowledge<F>(&mut self, mut handler: F)
+    where
+        F: FnMut(Result<Rc<Vec<WordList<N>>>, Vec<String>>),
+    {
+        if !self.has_new_knowledge {
+            return;
+        }
+        self.has_new_knowledge = false;
+        let mut open_boards = Vec::new();
+        let mut contradictions = Vec::new();
+        for (board, state) in self.game.states(&self.answers_list).into_iter().enumerate() {
+            self.headers[board] = match state {
+                BoardState::Solved(guesses) => format!("#{} solved in {}", board + 1, guesses),
+                BoardState::Open(words) => {
+                    let header = format!("#{} {} left", board + 1, words.len());
+                    open_boards.push(words);
+                    header
+                }
+                BoardState::Contradiction(found) => {
+                    contradictions
+                        .extend(found.iter().map(|c| format!("Board {}: {}", board + 1, c)));
+                    format!("#{} contradiction", board + 1)
+                }
+            };
+        }
+        self.redraw();
+        if contradictions.is_empty() {
+            handler(Ok(Rc::new(open_boards)));
+        } else {
+            handler(Err(contradictions));
+        }
+    }
+}
+
+impl<const N: usize> Widget for MultiBoardUI<N> {
+    fn title(&self) -> Option<String> {
+        None
+    }
+
+    fn set_active(&mut self, _active: bool) {
+        // nothing
+    }
+
+    fn handle_input(&mut self, input: Input) -> Option<Input> {
+        if self.game.is_over() {
+            return Some(input);
+        }
+        self.message.clear();
+        match input {
+            Input::KeyUp | Input::KeyDown => self.cycle_knowledge(input == Input::KeyUp),
+            Input::KeyRight | Input::KeyLeft => {
+                incr_usize(&mut self.active_col, N, input == Input::KeyRight, WRAP)
+            }
+            Input::Character(']') => self.cycle_board(true),
+            Input::Character('[') => self.cycle_board(false),
+            Input::Character('\n') => self.advance(),
+            Input::Character('\x7F') => self.unset_active_ch(), // delete
+            Input::Character(ch) if ch.is_ascii_alphabetic() => self.set_active_ch(ch),
+            _ => return Some(input),
+        }
+        self.redraw();
+        None
+    }
+}
+
+impl<const N: usize> MultiBoardUI<N> {
+    fn set_active_ch(&mut self, ch: char) {
+        let row = self.game.guess_count();
+        for board in self.game.unsolved_boards() {
+            let grid = self.game.board_mut(board);
+            // If this board already knows which letter goes here, and it's this one, it must be
+            // green.
+            let known = grid.known_chars()[self.active_col];
+            let cell = grid.guess_mut(row).guess_mut(self.active_col);
+            let had_knowledge = cell.knowledge() != CharKnowledge::Unknown;
+            cell.set_ch(ch);
+            if known == cell.ch() {
+                cell.set_knowledge(CharKnowledge::Correct);
+            }
+            self.has_new_knowledge |= had_knowledge || cell.knowledge() != CharKnowledge::Unknown;
+        }
+        incr_usize(&mut self.active_col, N, true, WRAP);
+    }
+
+    fn unset_active_ch(&mut self) {
+        let row = self.game.guess_count();
+        let mut had_ch = false;
+        for board in self.game.unsolved_boards() {
+            let cell = self
+                .game
+                .board_mut(board)
+                .guess_mut(row)
+                .guess_mut(self.active_col);
+            self.has_new_knowledge |= cell.knowledge() != CharKnowledge::Unknown;
+            had_ch |= cell.unset_ch().is_some();
+        }
+        if !had_ch {
+            incr_usize(&mut self.active_col, N, false, WRAP);
+        }
+    }
+
+    fn cycle_knowledge(&mut self, up: bool) {
+        if self.game.is_solved(self.active_board) {
+            return;
+        }
+        let row = self.game.guess_count();
+        let cell = self
+            .game
+            .board_mut(self.active_board)
+            .guess_mut(row)
+            .guess_mut(self.active_col);
+        let mut next_idx = cell.knowledge() as usize;
+        incr_usize(&mut next_idx, CharKnowledge::COUNT, up, WRAP);
+        let next = CharKnowledge::from_repr(next_idx)
+            .unwrap_or_else(|| panic!("out of range for {}", next_idx));
+        cell.set_knowledge(next);
+        self.has_new_knowledge = true;
+    }
+
+    /// Moves to the next (or previous) unsolved board.
+    fn cycle_board(&mut self, forward: bool) {
+        let board_count = self.game.boards().len();
+        for _ in 0..board_count {
+            incr_usize(&mut self.active_board, board_count, forward, WRAP);
+            if !self.game.is_solved(self.active_board) {
+                return;
+            }
+        }
+    }
+
+    fn advance(&mut self) {
+        match self.game.advance() {
+            Ok(()) => {
+                self.has_new_knowledge = true;
+                self.active_col = 0;
+                if self.game.is_solved(self.active_board) {
+                    self.cycle_board(true);
+                }
+                if self.game.all_solved() {
+                    self.message = format!("Solved in {}!", self.game.guess_count());
+                } else if self.game.is_over() {
+                    self.message = "Out of guesses.".to_string();
+                }
+            }
+            Err(e) => self.message = e,
+        }
+    }
+
+    fn redraw(&self) {
+        self.window.erase();
+        let current_row = self.game.guess_count();
+        for (board, grid) in self.game.boards().iter().enumerate() {
+            let top = (board / BOARDS_ACROSS) as i32 * Self::board_height(self.game.row_count());
+            let left = (board % BOARDS_ACROSS) as i32 * Self::BOARD_WIDTH;
+            let is_active = board == self.active_board && !self.game.is_over();
+            {
+                let window_state = WindowState::new(&self.window);
+                if is_active {
+                    window_state.set_color(Color::Warning);
+                }
+                _ = self.window.mvaddstr(top, left, &self.headers[board]);
+            }
+            let rows = self
+                .game
+                .solved_in(board)
+                .unwrap_or(current_row + 1)
+                .min(self.game.row_count());
+            for (row_idx, row) in grid.rows().take(rows).enumerate() {
+                for (col, cell) in row.chars().enumerate() {
+                    let (open, close) =
+                        if is_active && row_idx == current_row && col == self.active_col {
+                            ('[', ']')
+                        } else {
+                            (' ', ' ')
+                        };
+                    let window_state = WindowState::new(&self.window);
+                    window_state.set_color(color_for_knowledge(cell.knowledge()));
+                    _ = self.window.mvaddstr(
+                        top + 1 + row_idx as i32,
+                        left + 3 * col as i32,
+                        format!("{}{}{}", open, cell.ch().unwrap_or('.'), close),
+                    );
+                }
+            }
+        }
+        let status_y = self.window.get_max_y() - 1;
+        let window_state = WindowState::new(&self.window);
+        if self.message.is_empty() {
+            _ = self
+                .window
+                .mvaddstr(status_y, 0, "[ ]: switch boards; up/down: set feedback");
+        } else {
+            window_state.set_color(Color::Warning);
+            _ = self.window.mvaddstr(status_y, 0, &self.message);
+        }
+    }
+}
@@ -53,7 +53,7 @@ impl TextScroll {
 
 impl Widget for TextScroll {
     fn title(&self) -> Option<String> {
-        (&self.title).as_ref().map(|s| s.to_string())
+        self.title.as_ref().map(|s| s.to_string())
     }
 
     fn set_active(&mut self, _active: bool) {
@@ -91,7 +91,7 @@ impl TextScroll {
         let main_pane_width_usize = main_pane_width as usize;
 
         fn rep_str(ch: char, size: usize) -> String {
-            std::iter::repeat(ch).take(size).collect()
+            std::iter::repeat_n(ch, size).collect()
         }
 
         let main_pane_h_bar: String = rep_str('─', main_pane_width_usize);
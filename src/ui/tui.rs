@@ -1,37 +1,158 @@
-use crate::analyze::analyzer;
+use crate::analyze::analyzer::Analyzer;
+use crate::guess::guesses::GuessStr;
+use crate::guess::host::Host;
+use crate::guess::known_word_constraints::KnownWordConstraints;
+use crate::history;
+use crate::history::{GameMode, GameRecord, RecordedGuess, TopPick};
+use crate::session;
+use crate::session::Session;
 use crate::ui::analyzers_ui::AnalyzersUI;
+use crate::ui::explain_ui::ExplainUI;
 use crate::ui::guesses_ui::GuessesUI;
+use crate::ui::multi_board_ui::MultiBoardUI;
 use crate::ui::text_scroll_pane::TextScroll;
 use crate::ui::widget::Widget;
 use crate::ui::window_helper::init;
+use crate::util::{days_since_epoch, format_date};
+use crate::word_list::WordList;
 
 use pancurses::{endwin, Input, Window};
+use rayon::prelude::*;
+use std::path::PathBuf;
+use std::rc::Rc;
 
-pub struct MainWindow<const N: usize, const R: usize> {
+const ANALYZERS_COLS: i32 = 30;
+
+/// What kind of game `MainWindow::run_main_loop` runs, and where it goes when it's over.
+pub struct GameOptions<const N: usize> {
+    /// If there's a host, the user plays against it, and it gives the feedback for each guess;
+    /// otherwise, the user enters the feedback from a game they're playing elsewhere.
+    pub host: Option<Box<dyn Host<N>>>,
+    pub mode: GameMode,
+    /// Whether the analyzers' suggestions start out shown.
+    pub hints: bool,
+    /// The history file to record the game in, once it's over.
+    pub history: Option<PathBuf>,
+    /// The session file to save the grid to after each change, until the game is over. Only the
+    /// helper saves its sessions; a host's answer would have to be saved along with them.
+    pub session: Option<PathBuf>,
+    /// An earlier session to pick up from.
+    pub resume: Option<Session<N>>,
+}
+
+impl<const N: usize> GameOptions<N> {
+    /// The interactive helper, with no host and the suggestions shown.
+    pub fn helper(history: Option<PathBuf>) -> Self {
+        GameOptions {
+            host: None,
+            mode: GameMode::Helper,
+            hints: true,
+            history,
+            session: None,
+            resume: None,
+        }
+    }
+}
+
+pub struct MainWindow<const N: usize> {
     window: Window,
+    /// How many guesses each game allows.
+    rows: usize,
+    hard_mode: bool,
+    /// Problems that shouldn't end the game, like a history file that can't be written. They're
+    /// printed once the terminal is back to normal.
+    warnings: Vec<String>,
 }
 
-impl<const N: usize, const R: usize> Drop for MainWindow<N, R> {
+impl<const N: usize> Drop for MainWindow<N> {
     fn drop(&mut self) {
         endwin();
+        for warning in &self.warnings {
+            eprintln!("warning: {}", warning);
+        }
     }
 }
 
-impl<const N: usize, const R: usize> MainWindow<N, R> {
-    pub fn init() -> Self {
-        MainWindow { window: init() }
+impl<const N: usize> MainWindow<N> {
+    pub fn init(rows: usize, hard_mode: bool) -> Self {
+        MainWindow {
+            window: init(),
+            rows,
+            hard_mode,
+            warnings: Vec::new(),
+        }
     }
 
-    pub fn run_main_loop(&mut self) {
-        let mut guesses_ui: GuessesUI<N, R> = GuessesUI::new(&self.window, 0, 0);
+    /// Runs the TUI until the user quits. Fails if the terminal is too small for the grid. If the
+    /// session or the finished game can't be saved, that's a warning for when the TUI exits.
+    ///
+    /// ctrl-g hides or shows the analyzers' suggestions, whatever kind of game this is.
+    pub fn run_main_loop(
+        &mut self,
+        words_list: WordList<N>,
+        answers_list: WordList<N>,
+        analyzers: Vec<Box<dyn Analyzer<N>>>,
+        options: GameOptions<N>,
+    ) -> Result<(), String> {
+        // The guesses are on the left, with the explain prompt below them, and the analyzers to
+        // their right. Longer words need more room for the guesses, and more rows need more height.
+        let left_width = (GuessesUI::<N>::WIDTH + 1).max(34);
+        let guesses_height = GuessesUI::<N>::height(self.rows);
+        let needed_lines = guesses_height + ExplainUI::<N>::HEIGHT;
+        let needed_cols = left_width + ANALYZERS_COLS;
+        self.check_size(
+            needed_lines,
+            needed_cols,
+            &format!("{} rows of {}-letter words", self.rows, N),
+        )?;
+
+        let mut guesses_ui: GuessesUI<N> = GuessesUI::new(
+            &self.window,
+            0,
+            0,
+            self.rows,
+            words_list.clone(),
+            answers_list.clone(),
+            options.host,
+        );
+        guesses_ui.set_hard_mode(self.hard_mode);
+        let mut explain_ui: ExplainUI<N> =
+            ExplainUI::new(&self.window, guesses_height, 0, left_width - 1);
 
         let mut analyzers_ui = AnalyzersUI::new(
-            self.create_text_scroll(None, 30, 0, 34),
-            analyzer::standard_suite(),
+            self.create_text_scroll(None, ANALYZERS_COLS, 0, left_width),
+            analyzers,
         );
+        analyzers_ui.set_hidden(!options.hints);
+        if let Some(resume) = options.resume {
+            guesses_ui.restore(resume)?;
+        }
+        let mut recorder = options
+            .history
+            .map(|path| GameRecorder::new(path, options.mode, words_list.clone(), answers_list));
+        let mut session_saver = options
+            .session
+            .map(|path| SessionSaver::new(path, &guesses_ui));
 
         loop {
-            guesses_ui.handle_new_knowledge(|possible_words| analyzers_ui.analyze(possible_words));
+            guesses_ui.handle_new_knowledge(|knowledge| match knowledge {
+                Ok((allowed_guesses, possible_words)) => {
+                    analyzers_ui.analyze(allowed_guesses, possible_words)
+                }
+                Err(contradictions) => analyzers_ui.show_contradictions(&contradictions),
+            });
+            if let Some(recorder) = recorder.as_mut() {
+                if let Err(e) =
+                    recorder.update(&guesses_ui, analyzers_ui.analyzers(), self.hard_mode)
+                {
+                    self.warn(e);
+                }
+            }
+            if let Some(session_saver) = session_saver.as_mut() {
+                if let Err(e) = session_saver.update(&guesses_ui) {
+                    self.warn(e);
+                }
+            }
 
             self.refresh();
 
@@ -40,14 +161,32 @@ impl<const N: usize, const R: usize> MainWindow<N, R> {
 
             if let Some(input) = maybe_input {
                 match input {
-                    Input::Character(c) if c == '\x03' => {
+                    Input::Character('\x03') => {
                         // ctrl-c
-                        return;
+                        return Ok(());
+                    }
+                    Input::Character('\x14') => {
+                        // ctrl-t
+                        self.hard_mode = !self.hard_mode;
+                        guesses_ui.set_hard_mode(self.hard_mode);
+                    }
+                    Input::Character('\x07') => {
+                        // ctrl-g
+                        analyzers_ui.set_hidden(!analyzers_ui.is_hidden());
+                    }
+                    Input::Character('\x17') => {
+                        // ctrl-w
+                        explain_ui.set_active(!explain_ui.is_active());
+                    }
+                    _ if explain_ui.is_active() => {
+                        if let Some(Input::Character('\n')) = explain_ui.handle_input(input) {
+                            explain_ui.explain(guesses_ui.grid(), &words_list);
+                        }
                     }
                     _ => {
                         for widget in widgets.iter_mut() {
                             maybe_input = widget.handle_input(input);
-                            if maybe_input == None {
+                            if maybe_input.is_none() {
                                 break;
                             }
                         }
@@ -57,6 +196,78 @@ impl<const N: usize, const R: usize> MainWindow<N, R> {
         }
     }
 
+    /// Like `run_main_loop`, but for a multi-board game (see `MultiBoard`). Hard mode and the
+    /// explain prompt aren't available here.
+    ///
+    /// [`MultiBoard`]: crate::guess::multi_board::MultiBoard
+    pub fn run_multi_board_loop(
+        &mut self,
+        words_list: WordList<N>,
+        answers_list: WordList<N>,
+        analyzers: Vec<Box<dyn Analyzer<N>>>,
+        board_count: usize,
+    ) -> Result<(), String> {
+        let (boards_lines, boards_cols) = MultiBoardUI::<N>::size(board_count, self.rows);
+        self.check_size(
+            boards_lines,
+            boards_cols + ANALYZERS_COLS,
+            &format!("{} boards of {} rows", board_count, self.rows),
+        )?;
+
+        let mut boards_ui: MultiBoardUI<N> =
+            MultiBoardUI::new(&self.window, 0, 0, board_count, self.rows, answers_list);
+        let mut analyzers_ui = AnalyzersUI::new(
+            self.create_text_scroll(None, ANALYZERS_COLS, 0, boards_cols),
+            analyzers,
+        );
+        let guesses_list = Rc::new(words_list);
+
+        loop {
+            boards_ui.handle_new_knowledge(|knowledge| match knowledge {
+                Ok(boards) => analyzers_ui.analyze_boards(guesses_list.clone(), boards),
+                Err(contradictions) => analyzers_ui.show_contradiction_texts(contradictions),
+            });
+
+            self.refresh();
+
+            let Some(input) = self.get_input() else {
+                continue;
+            };
+            if input == Input::Character('\x03') {
+                // ctrl-c
+                return Ok(());
+            }
+            let mut widgets: Vec<&mut dyn Widget> = vec![(&mut boards_ui), (&mut analyzers_ui)];
+            let mut maybe_input = Some(input);
+            for widget in widgets.iter_mut() {
+                if let Some(input) = maybe_input {
+                    maybe_input = widget.handle_input(input);
+                }
+            }
+        }
+    }
+
+    /// Adds a warning to print on exit, unless it's already there.
+    fn warn(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    fn check_size(&self, needed_lines: i32, needed_cols: i32, what: &str) -> Result<(), String> {
+        if self.window.get_max_y() < needed_lines || self.window.get_max_x() < needed_cols {
+            return Err(format!(
+                "the terminal is too small for {}: it needs to be at least {}x{}, but it's {}x{}",
+                what,
+                needed_cols,
+                needed_lines,
+                self.window.get_max_x(),
+                self.window.get_max_y()
+            ));
+        }
+        Ok(())
+    }
+
     pub fn refresh(&self) {
         self.window.touch();
         self.window.refresh();
@@ -82,3 +293,339 @@ impl<const N: usize, const R: usize> MainWindow<N, R> {
         )
     }
 }
+
+/// Saves the game to the history file once it's over. Each analyzer's top pick for a row is worked
+/// out once, when that row is scored, so that there's nothing left to do when the game ends.
+struct GameRecorder<const N: usize> {
+    path: PathBuf,
+    mode: GameMode,
+    /// The allowed guesses and possible answers that the game started with, to work out what the
+    /// analyzers would have guessed at each step.
+    guesses_list: WordList<N>,
+    answers_list: WordList<N>,
+    /// The active row, and its possible answers from before anything was entered in it. The grid
+    /// already has these, so there's no need to filter the answers again when the row is scored.
+    row_start: Option<(usize, WordList<N>)>,
+    /// Each scored row's top picks, in order.
+    top_picks: Vec<Vec<TopPick>>,
+    saved: bool,
+}
+
+impl<const N: usize> GameRecorder<N> {
+    fn new(
+        path: PathBuf,
+        mode: GameMode,
+        guesses_list: WordList<N>,
+        answers_list: WordList<N>,
+    ) -> Self {
+        GameRecorder {
+            path,
+            mode,
+            guesses_list,
+            answers_list,
+            row_start: None,
+            top_picks: Vec::new(),
+            saved: false,
+        }
+    }
+
+    /// Works out the top picks for any rows that were scored since the last update, and saves the
+    /// game if it's just ended.
+    fn update(
+        &mut self,
+        guesses_ui: &GuessesUI<N>,
+        analyzers: &[Box<dyn Analyzer<N>>],
+        hard_mode: bool,
+    ) -> Result<(), String> {
+        if self.saved {
+            return Ok(());
+        }
+        // The session has every row, including any that were restored from an earlier run.
+        let session = guesses_ui.session();
+        let game_over = guesses_ui.is_game_over();
+        self.score_rows(
+            &session,
+            game_over,
+            guesses_ui.possible_words_at_row_start(),
+            analyzers,
+            hard_mode,
+        );
+        if !game_over {
+            return Ok(());
+        }
+        self.saved = true;
+        let record = GameRecord {
+            date: format_date(days_since_epoch()),
+            mode: self.mode,
+            answer: guesses_ui.answer(),
+            won: guesses_ui.is_won(),
+            guesses: self.recorded_guesses(&session.rows),
+        };
+        history::append(&self.path, &record)
+    }
+
+    /// Works out the top picks for the rows in `session` that have been scored but don't have them
+    /// yet, in `hard_mode`: that's the setting that each of them was scored with. Then notes the
+    /// possible answers at the start of the active row, for when it's scored.
+    fn score_rows(
+        &mut self,
+        session: &Session<N>,
+        game_over: bool,
+        possible_words_at_row_start: &WordList<N>,
+        analyzers: &[Box<dyn Analyzer<N>>],
+        hard_mode: bool,
+    ) {
+        let scored = if game_over {
+            session.active_row + 1
+        } else {
+            session.active_row
+        };
+        while self.top_picks.len() < scored {
+            let row = self.top_picks.len();
+            let top_picks = self.top_picks_after(&session.rows[..row], analyzers, hard_mode);
+            self.top_picks.push(top_picks);
+        }
+        if !game_over && self.row_start.as_ref().map(|(row, _)| *row) != Some(session.active_row) {
+            self.row_start = Some((session.active_row, possible_words_at_row_start.clone()));
+        }
+    }
+
+    /// What each analyzer would have guessed after `rows_before`.
+    fn top_picks_after(
+        &self,
+        rows_before: &[GuessStr<N>],
+        analyzers: &[Box<dyn Analyzer<N>>],
+        hard_mode: bool,
+    ) -> Vec<TopPick> {
+        let mut constraints = KnownWordConstraints::empty();
+        for row in rows_before {
+            constraints.add_row(row);
+        }
+        // Rows from a resumed session were scored before we saw them start.
+        let possible_words = match &self.row_start {
+            Some((row, possible_words)) if *row == rows_before.len() => possible_words.clone(),
+            _ => self.answers_list.filter_preview(&constraints),
+        };
+        let allowed_guesses = if hard_mode {
+            self.guesses_list.filter_preview(&constraints.hard_mode())
+        } else {
+            self.guesses_list.clone()
+        };
+        // This runs while the user waits for the next row, so spread it across the cores.
+        analyzers
+            .par_iter()
+            .filter_map(|analyzer| {
+                let mut scored = analyzer.analyze(&allowed_guesses, &possible_words);
+                scored.sort();
+                scored.first().map(|top| TopPick {
+                    analyzer: analyzer.name(),
+                    word: top.word.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Each of `rows`, with the top picks that were worked out for it.
+    fn recorded_guesses(&mut self, rows: &[GuessStr<N>]) -> Vec<RecordedGuess> {
+        rows.iter()
+            .zip(std::mem::take(&mut self.top_picks))
+            .map(|(row, top_picks)| RecordedGuess {
+                word: row.word(),
+                feedback: row.feedback(),
+                top_picks,
+            })
+            .collect()
+    }
+}
+
+/// Saves the grid to the session file whenever it changes, and removes the file once the game is
+/// over. If a save fails, the next change tries again.
+struct SessionSaver {
+    path: PathBuf,
+    /// What was last saved, as JSON; or what the grid started out as, so that a session isn't
+    /// replaced until something's actually entered.
+    last_saved: String,
+    cleared: bool,
+}
+
+impl SessionSaver {
+    fn new<const N: usize>(path: PathBuf, guesses_ui: &GuessesUI<N>) -> Self {
+        SessionSaver {
+            path,
+            last_saved: Self::to_json(&guesses_ui.session()),
+            cleared: false,
+        }
+    }
+
+    fn update<const N: usize>(&mut self, guesses_ui: &GuessesUI<N>) -> Result<(), String> {
+        if guesses_ui.is_game_over() {
+            if !self.cleared {
+                self.cleared = true;
+                session::clear(&self.path)?;
+            }
+            return Ok(());
+        }
+        let session = guesses_ui.session();
+        let json = Self::to_json(&session);
+        if json != self.last_saved {
+            session::save(&self.path, &session)?;
+            self.last_saved = json;
+        }
+        Ok(())
+    }
+
+    fn to_json<const N: usize>(session: &Session<N>) -> String {
+        serde_json::to_string(session).expect("sessions always serialize")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analyze::analyzer::{select_from_standard_suite, ScoredWord};
+    use crate::word_list::WordFreq;
+    use std::fs;
+
+    /// Picks the most common allowed guess, whether or not it could be the answer.
+    struct MostCommonGuess;
+
+    impl Analyzer<5> for MostCommonGuess {
+        fn name(&self) -> String {
+            "Most Common Guess".to_string()
+        }
+
+        fn analyze<'a>(
+            &self,
+            guesses: &'a WordList<5>,
+            _words_list: &'a WordList<5>,
+        ) -> Vec<ScoredWord<'a>> {
+            guesses
+                .words()
+                .map(|WordFreq { word, freq }| ScoredWord { word, score: *freq })
+                .collect()
+        }
+    }
+
+    fn session(rows: &[&str], active_row: usize) -> Session<5> {
+        Session {
+            rows: rows.iter().map(|row| row.parse().unwrap()).collect(),
+            active_row,
+            active_col: 0,
+        }
+    }
+
+    fn summary(recorded: Vec<RecordedGuess>) -> Vec<(String, String)> {
+        recorded
+            .into_iter()
+            .map(|g| (g.word, g.top_picks[0].word.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn top_picks_come_from_the_rows_before() {
+        let words = WordList::from_words(&["CRANE", "SLATE", "STAIR", "TRAIN"]);
+        let mut recorder = GameRecorder::new(
+            PathBuf::new(),
+            GameMode::Helper,
+            words.clone(),
+            words.clone(),
+        );
+        let analyzers = select_from_standard_suite(&["most common words".to_string()]).unwrap();
+
+        let first = session(&["SLATE:....."], 0);
+        recorder.score_rows(&first, false, &words, &analyzers, false);
+        assert!(recorder.top_picks.is_empty());
+
+        let second = session(&["SLATE:GBGYB", "STAIR:....."], 1);
+        let mut constraints = KnownWordConstraints::empty();
+        constraints.add_row(&second.rows[0]);
+        let possible_words = words.filter_preview(&constraints);
+        recorder.score_rows(&second, false, &possible_words, &analyzers, false);
+        assert_eq!(1, recorder.top_picks.len());
+
+        let over = session(&["SLATE:GBGYB", "STAIR:GGGGG"], 1);
+        recorder.score_rows(&over, true, &possible_words, &analyzers, false);
+        assert_eq!(
+            vec![
+                ("SLATE".to_string(), "CRANE".to_string()),
+                ("STAIR".to_string(), "STAIR".to_string()),
+            ],
+            summary(recorder.recorded_guesses(&over.rows))
+        );
+    }
+
+    /// A game that was resumed from a session file is recorded with the rows from before it was
+    /// resumed, and each of them gets the picks it would have had then.
+    #[test]
+    fn resumed_session() {
+        let dir = std::env::temp_dir().join(format!("wordlehelper-resumed-{}", std::process::id()));
+        let path = dir.join("session.json");
+        session::save(&path, &session(&["SLATE:GBGYB", "STAIR:....."], 1)).unwrap();
+        let mut resumed: Session<5> = session::load(&path).unwrap().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let words = WordList::from_words(&["CRANE", "SLATE", "STAIR", "TRAIN"]);
+        let mut recorder = GameRecorder::new(
+            PathBuf::new(),
+            GameMode::Helper,
+            words.clone(),
+            words.clone(),
+        );
+        let analyzers = select_from_standard_suite(&["most common words".to_string()]).unwrap();
+        // Restoring the session leaves the grid with the possible answers from the rows before
+        // the active one.
+        let mut constraints = KnownWordConstraints::empty();
+        constraints.add_row(&resumed.rows[0]);
+        let possible_words = words.filter_preview(&constraints);
+        recorder.score_rows(&resumed, false, &possible_words, &analyzers, false);
+
+        resumed.rows[1] = "STAIR:GGGGG".parse().unwrap();
+        recorder.score_rows(&resumed, true, &possible_words, &analyzers, false);
+        assert_eq!(
+            vec![
+                ("SLATE".to_string(), "CRANE".to_string()),
+                ("STAIR".to_string(), "STAIR".to_string()),
+            ],
+            summary(recorder.recorded_guesses(&resumed.rows))
+        );
+    }
+
+    /// Each row's picks are for the hard mode setting it was scored with, even if that changed
+    /// after the row was started.
+    #[test]
+    fn hard_mode_of_each_row() {
+        let words = WordList::from_words(&["CRANE", "SLATE", "STAIR", "TRAIN"]);
+        let mut recorder = GameRecorder::new(
+            PathBuf::new(),
+            GameMode::Helper,
+            words.clone(),
+            words.clone(),
+        );
+        let analyzers: Vec<Box<dyn Analyzer<5>>> = vec![Box::new(MostCommonGuess)];
+
+        recorder.score_rows(
+            &session(&["SLATE:....."], 0),
+            false,
+            &words,
+            &analyzers,
+            false,
+        );
+        recorder.score_rows(
+            &session(&["SLATE:GBGYB", "STAIR:....."], 1),
+            false,
+            &words,
+            &analyzers,
+            false,
+        );
+        let over = session(&["SLATE:GBGYB", "STAIR:GGGGG"], 1);
+        recorder.score_rows(&over, true, &words, &analyzers, true);
+        // In hard mode, CRANE can't be guessed after SLATE's S, A and T.
+        assert_eq!(
+            vec![
+                ("SLATE".to_string(), "CRANE".to_string()),
+                ("STAIR".to_string(), "SLATE".to_string()),
+            ],
+            summary(recorder.recorded_guesses(&over.rows))
+        );
+    }
+}
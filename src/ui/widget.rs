@@ -1,7 +1,9 @@
 use pancurses::Input;
 
 pub trait Widget {
+    #[allow(dead_code)]
     fn title(&self) -> Option<String>;
+    #[allow(dead_code)]
     fn set_active(&mut self, active: bool);
     fn handle_input(&mut self, input: Input) -> Option<Input>;
 }
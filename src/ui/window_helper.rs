@@ -28,16 +28,16 @@ impl<'a> Drop for WindowState<'a> {
 }
 
 impl<'a> WindowState<'a> {
-    pub fn new(window: &Window) -> WindowState {
+    pub fn new(window: &Window) -> WindowState<'_> {
         let (orig_y, orig_x) = window.get_cur_yx();
         let (orig_attrs, orig_color) = window.attrget();
-        return WindowState {
+        WindowState {
             orig_y,
             orig_x,
             window,
             orig_attrs,
             orig_color,
-        };
+        }
     }
 
     pub fn set_color(&self, color: Color) {
@@ -1,4 +1,7 @@
 use std::cmp::min;
+use std::env;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn incr_usize(u: &mut usize, max_exclusive: usize, up: bool, wrap: bool) {
     *u = match (u.checked_add_signed(if up { 1 } else { -1 }), wrap) {
@@ -11,3 +14,57 @@ pub fn incr_usize(u: &mut usize, max_exclusive: usize, up: bool, wrap: bool) {
 
 pub const WRAP: bool = true;
 pub const NO_WRAP: bool = false;
+
+/// Where we keep the files that outlive a run, like the game history: `wordlehelper`, under
+/// `$XDG_DATA_HOME` or `~/.local/share`. `None` if neither of those is set.
+pub fn data_dir() -> Option<PathBuf> {
+    let data_home = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(data_home.join("wordlehelper"))
+}
+
+/// Today's date (in UTC), as the number of days since the Unix epoch.
+pub fn days_since_epoch() -> u64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    since_epoch.as_secs() / (24 * 60 * 60)
+}
+
+/// Formats a day from `days_since_epoch` as `YYYY-MM-DD`. This is Howard Hinnant's
+/// `civil_from_days` algorithm, which counts in 400-year eras starting from March 1st, so that
+/// leap days come at the end of each year.
+pub fn format_date(days_since_epoch: u64) -> String {
+    let days = days_since_epoch + 719_468; // from 0000-03-01 instead of 1970-01-01
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        assert_eq!("1970-01-01", format_date(0));
+        assert_eq!("2000-02-29", format_date(11_016));
+        assert_eq!("2000-03-01", format_date(11_017));
+        assert_eq!("2024-12-31", format_date(20_088));
+    }
+}
@@ -1,149 +1,124 @@
+use crate::analyze::pattern_matrix::PatternMatrix;
+use crate::guess::compiled_constraints::EncodedWord;
 use crate::guess::known_word_constraints::KnownWordConstraints;
 use crate::word_list::Iter::ForFiltered;
 use crate::word_list::WordList::{Empty, Filtered, Reified};
 use bitvec::prelude::*;
-use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::iter::FlatMap;
-use std::rc::Rc;
 use std::str::Chars;
-use std::usize;
-use strum::EnumIter;
-use strum::IntoEnumIterator;
+use std::sync::Arc;
 
-const STD_WORD_LIST_SIZE: usize = 7_500;
-
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct WordFreq {
     pub word: String,
     pub freq: f64,
 }
 
+/// A list of words, most common first. Each word also has an `EncodedWord`, at the same index in
+/// `encoded`, for filtering.
+///
+/// The list can also carry a `PatternMatrix` for pattern-based analyzers. Filtering keeps it, since
+/// the matrix looks words up by their strings; it just covers more words than the list needs.
 #[derive(Clone)]
 pub enum WordList<const N: usize> {
     Empty,
     Reified {
-        words: Rc<Vec<WordFreq>>,
+        words: Arc<Vec<WordFreq>>,
+        encoded: Arc<Vec<EncodedWord<N>>>,
+        patterns: Option<Arc<PatternMatrix<N>>>,
     },
     Filtered {
-        words: Rc<Vec<WordFreq>>,
+        words: Arc<Vec<WordFreq>>,
+        encoded: Arc<Vec<EncodedWord<N>>>,
         allowed: BitVec<usize, Lsb0>,
+        patterns: Option<Arc<PatternMatrix<N>>>,
     },
 }
 
-#[derive(EnumIter)]
-pub enum WordsFile {
-    WGutenberg,
-    Norvig,
-    HermitDave,
-}
-
-impl WordsFile {
-    pub fn get_embedded<const N: usize>(&self, limit: usize) -> WordList<N> {
-        let file = self.get_file_contents();
-        let mut words = Vec::with_capacity(file.chars().filter(|c| c == &'\n').count());
-        for line in file.split("\n") {
-            let Some((word, freq_str)) = line.split_once("\t") else {
-                continue;
-            };
-            if word.len() != N {
-                continue;
-            }
-            let Ok(freq) = freq_str.parse::<f64>() else {
-                continue;
-            };
-            words.push(WordFreq {
-                word: word.to_ascii_uppercase(),
-                freq,
-            });
-            if words.len() >= limit {
-                break;
-            }
-        }
-        return Reified {
-            words: Rc::new(words),
-        };
-    }
-}
-
-impl WordsFile {
-    fn get_file_contents(&self) -> &'static str {
-        match self {
-            WordsFile::WGutenberg => include_str!("words-5chars-wiktionary-gutenberg.txt"),
-            WordsFile::Norvig => include_str!("words-5chars-norvig.txt"),
-            WordsFile::HermitDave => include_str!("words-5chars-hermitdave.txt"),
-        }
-    }
-
-    fn multiplier(&self) -> f64 {
-        match self {
-            WordsFile::WGutenberg => 5.0,
-            WordsFile::Norvig => 0.0,
-            WordsFile::HermitDave => 1.0,
-        }
-    }
-}
-
 impl<const N: usize> WordList<N> {
     pub fn empty() -> Self {
         Empty
     }
 
-    pub fn std() -> Self {
-        Self::combine(
-            WordsFile::iter().map(|wl| (wl.get_embedded(STD_WORD_LIST_SIZE * 2), wl.multiplier())),
-            STD_WORD_LIST_SIZE,
+    /// A small list for tests: `words`, most common first, with the frequencies that a plain
+    /// words file would give them (see `WordsFileFormat::Plain`).
+    ///
+    /// [`WordsFileFormat::Plain`]: crate::external_words::WordsFileFormat::Plain
+    #[cfg(test)]
+    pub fn from_words(words: &[&str]) -> Self {
+        WordList::new(
+            words
+                .iter()
+                .enumerate()
+                .map(|(rank, word)| WordFreq {
+                    word: word.to_ascii_uppercase(),
+                    freq: 1.0 / (rank + 1) as f64,
+                })
+                .collect(),
         )
     }
 
-    pub fn combine<I>(items: I, limit: usize) -> Self
-    where
-        I: Iterator<Item = (Self, f64)>,
-    {
-        // There may be a more clever way to do this, in a streaming fashion. But for now, I'm
-        // just going to do the brute-force approach.
-        // let itemsRef = &items;
-        let mut acc: HashMap<String, f64> = HashMap::new();
-        for (word_list, factor) in items {
-            let total_freqs: f64 = word_list.words().map(|wf| wf.freq).sum();
-            for word_freq in word_list.words() {
-                let entry = acc.entry(word_freq.word.clone());
-                *entry.or_insert(0.0) += word_freq.freq / total_freqs * factor;
-            }
+    /// Creates a list of the given words, which should be sorted from most to least common.
+    pub fn new(words: Vec<WordFreq>) -> Self {
+        let encoded = words.iter().map(|wf| EncodedWord::new(&wf.word)).collect();
+        Reified {
+            words: Arc::new(words),
+            encoded: Arc::new(encoded),
+            patterns: None,
         }
-        // We could be more efficient with this: rather than coming up with the full list, and
-        // then trimming it, we could inert-and-trim as we go. Not important for now, though.
-        let mut acc_vec: Vec<WordFreq> = acc
-            .into_iter()
-            .map(|(word, freq)| WordFreq { word, freq })
-            .collect();
-        acc_vec.sort_by(|first, second| match second.freq.total_cmp(&first.freq) {
-            Ordering::Equal => first.word.cmp(&second.word),
-            ne => ne,
-        });
-        acc_vec.truncate(limit);
-        // We could compact now, but probably not worth the CPU. It'll get compacted next time
-        // it's filtered, anyway.
+    }
 
-        Reified {
-            words: Rc::new(acc_vec),
+    /// Attaches `matrix` to this list, for pattern-based analyzers to use. It should cover all of
+    /// the list's words; any that it doesn't will have their patterns computed as needed.
+    pub fn with_pattern_matrix(self, matrix: Arc<PatternMatrix<N>>) -> Self {
+        match self {
+            Empty => Empty,
+            Reified { words, encoded, .. } => Reified {
+                words,
+                encoded,
+                patterns: Some(matrix),
+            },
+            Filtered {
+                words,
+                encoded,
+                allowed,
+                ..
+            } => Filtered {
+                words,
+                encoded,
+                allowed,
+                patterns: Some(matrix),
+            },
+        }
+    }
+
+    pub fn pattern_matrix(&self) -> Option<&Arc<PatternMatrix<N>>> {
+        match self {
+            Empty => None,
+            Reified { patterns, .. } | Filtered { patterns, .. } => patterns.as_ref(),
         }
     }
 
     pub fn filter(&mut self, knowledge: &KnownWordConstraints<N>) {
+        let compiled = knowledge.compile();
         match self {
             Empty => {}
-            Reified { words } => {
-                let existing = words.as_ref();
-                let mut new = existing.clone();
-                new.retain(|word| knowledge.is_word_possible(&word.word));
-                *words = Rc::new(new);
+            Reified { words, encoded, .. } => {
+                let (new_words, new_encoded) = words
+                    .iter()
+                    .zip(encoded.iter())
+                    .filter(|(_, enc)| compiled.is_word_possible(enc))
+                    .map(|(word, enc)| (word.clone(), enc.clone()))
+                    .unzip();
+                *words = Arc::new(new_words);
+                *encoded = Arc::new(new_encoded);
             }
-            Filtered { words, allowed } => {
+            Filtered {
+                encoded, allowed, ..
+            } => {
                 let mut remove: BitVec<usize, Lsb0> = BitVec::repeat(false, allowed.len());
                 for idx in allowed.iter_ones() {
-                    let word = &words[idx].word;
-                    if !knowledge.is_word_possible(word) {
+                    if !compiled.is_word_possible(&encoded[idx]) {
                         remove.set(idx, true);
                     }
                 }
@@ -157,27 +132,40 @@ impl<const N: usize> WordList<N> {
     pub fn filter_preview(&self, knowledge: &KnownWordConstraints<N>) -> Self {
         let mut new = match self {
             Empty => Empty,
-            Reified { words } => Filtered {
+            Reified {
+                words,
+                encoded,
+                patterns,
+            } => Filtered {
                 words: words.clone(),
+                encoded: encoded.clone(),
                 allowed: BitVec::repeat(true, words.as_ref().len()),
+                patterns: patterns.clone(),
             },
-            Filtered { words, allowed } => Filtered {
+            Filtered {
+                words,
+                encoded,
+                allowed,
+                patterns,
+            } => Filtered {
                 words: words.clone(),
+                encoded: encoded.clone(),
                 allowed: allowed.clone(),
+                patterns: patterns.clone(),
             },
         };
         new.filter(knowledge);
         new
     }
 
-    pub fn words(&self) -> Iter {
+    pub fn words(&self) -> Iter<'_> {
         match self {
             Empty => Iter::ForEmpty,
-            Reified { words } => Iter::ForReified {
+            Reified { words, .. } => Iter::ForReified {
                 iter: words.iter(),
                 total_length: words.len(),
             },
-            Filtered { words, allowed } => ForFiltered {
+            Filtered { words, allowed, .. } => ForFiltered {
                 all_words: words,
                 allowed_words: allowed.iter_ones(),
                 total_length: allowed.count_ones(),
@@ -185,28 +173,40 @@ impl<const N: usize> WordList<N> {
         }
     }
 
-    pub fn all_chars(&self) -> FlatMap<Iter, Chars<'_>, fn(&WordFreq) -> Chars<'_>> {
+    pub fn all_chars(&self) -> FlatMap<Iter<'_>, Chars<'_>, fn(&WordFreq) -> Chars<'_>> {
         self.words().flat_map(|w| w.word.chars())
     }
 
     pub fn len(&self) -> usize {
         match self {
             Empty => 0,
-            Reified { words } => words.as_ref().len(),
+            Reified { words, .. } => words.as_ref().len(),
             Filtered { allowed, .. } => allowed.count_ones(),
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn reify(&self) -> Self {
         match self {
             Empty => Empty,
-            Reified { words } => Reified {
-                words: Rc::clone(words),
-            },
-            f @ Filtered { .. } => {
-                let words: Vec<WordFreq> = f.words().map(|wf| wf.clone()).collect();
+            r @ Reified { .. } => r.clone(),
+            Filtered {
+                words,
+                encoded,
+                allowed,
+                patterns,
+            } => {
+                let (words, encoded) = allowed
+                    .iter_ones()
+                    .map(|idx| (words[idx].clone(), encoded[idx].clone()))
+                    .unzip();
                 Reified {
-                    words: Rc::new(words),
+                    words: Arc::new(words),
+                    encoded: Arc::new(encoded),
+                    patterns: patterns.clone(),
                 }
             }
         }
@@ -237,7 +237,7 @@ impl<'a> Iterator for Iter<'a> {
                 all_words,
                 allowed_words,
                 ..
-            } => allowed_words.next().map(|idx| all_words.get(idx)).flatten(),
+            } => allowed_words.next().and_then(|idx| all_words.get(idx)),
         }
     }
 }
@@ -0,0 +1,466 @@
+use crate::external_words::{load_words_file, split_weight, WordsFileFormat, WordsFileSpec};
+use crate::word_list::{WordFreq, WordList};
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use strum::{EnumIter, IntoEnumIterator};
+
+/// Which word lists go into the `WordList` the analyzers see, how much each one counts for, and
+/// how many words to keep. It can be read from a JSON file, like:
+///
+/// ```json
+/// {
+///     "sources": [
+///         {"embedded": "gutenberg", "weight": 5},
+///         {"embedded": "hermitdave"},
+///         {"file": {"path": "answers.txt", "format": "answers"}, "weight": 2}
+///     ],
+///     "limit": 5000,
+///     "normalize": "total"
+/// }
+/// ```
+///
+/// Anything that's left out gets its default (see `WordListConfig::default`), and a source's
+/// weight defaults to 1.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WordListConfig {
+    pub sources: Vec<SourceConfig>,
+    /// The most words to keep, after combining the sources.
+    pub limit: usize,
+    pub normalize: Normalize,
+}
+
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct SourceConfig {
+    #[serde(flatten)]
+    pub source: Source,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Embedded(WordsFile),
+    File {
+        path: PathBuf,
+        /// If this isn't given, it's detected from the file; see `load_words_file`.
+        #[serde(default)]
+        format: Option<WordsFileFormat>,
+    },
+}
+
+/// The longest words that `WordsFile::lengths` looks for in the embedded lists.
+const MAX_EMBEDDED_LENGTH: usize = 8;
+
+/// One of the word lists that are embedded in the binary.
+#[derive(EnumIter, Copy, Clone, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordsFile {
+    #[serde(rename = "gutenberg")]
+    WGutenberg,
+    Norvig,
+    HermitDave,
+}
+
+/// How to scale each list's frequencies before `WordList::combine` weights and adds them up. Lists
+/// count their frequencies in very different ways (raw counts, per-million, ranks...), so without
+/// this, one list's weight wouldn't mean much next to another's.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalize {
+    /// Divide by the list's total, so that each list's frequencies add up to 1.
+    #[default]
+    Total,
+    /// Divide by the list's highest frequency, so that its most common word has 1.
+    Max,
+    /// Ignore the frequencies, and use the words' ranks instead: the `n`th word gets `1/n`.
+    Rank,
+    /// Use the frequencies as they are.
+    None,
+}
+
+/// The embedded sources to use, as given on the command line: `NAME[=WEIGHT]`, where `NAME` is
+/// one of the embedded files, `std` for all of them at their default weights (each multiplied by
+/// `WEIGHT`), or `none`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct EmbeddedSources(pub Vec<SourceConfig>);
+
+impl Default for WordListConfig {
+    /// The standard list: mostly Project Gutenberg's frequencies, with a bit of HermitDave's. The
+    /// Norvig list has no weight, so it only contributes words that the others don't have, and
+    /// those come last.
+    fn default() -> Self {
+        WordListConfig {
+            sources: vec![
+                SourceConfig::embedded(WordsFile::WGutenberg, 5.0),
+                SourceConfig::embedded(WordsFile::Norvig, 0.0),
+                SourceConfig::embedded(WordsFile::HermitDave, 1.0),
+            ],
+            limit: 7_500,
+            normalize: Normalize::Total,
+        }
+    }
+}
+
+impl WordListConfig {
+    /// Reads a config from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("invalid config {}: {}", path.display(), e))
+    }
+
+    /// Replaces all of the embedded sources with `embedded`, keeping any file sources.
+    pub fn set_embedded(&mut self, embedded: Vec<SourceConfig>) {
+        self.sources
+            .retain(|s| !matches!(s.source, Source::Embedded(_)));
+        self.sources.splice(0..0, embedded);
+    }
+
+    /// Loads all of the sources, and combines them.
+    pub fn load<const N: usize>(&self) -> Result<WordList<N>, String> {
+        if self.sources.is_empty() {
+            return Err("no word lists to use".to_string());
+        }
+        let mut lists = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            lists.push((source.load(self.limit)?, source.weight));
+        }
+        let words = WordList::combine(lists.into_iter(), self.limit, self.normalize);
+        if words.is_empty() {
+            let embedded_lengths: BTreeSet<usize> =
+                WordsFile::iter().flat_map(|file| file.lengths()).collect();
+            let embedded_lengths: Vec<String> =
+                embedded_lengths.iter().map(|len| len.to_string()).collect();
+            return Err(format!(
+                "there are no {}-letter words in the word lists (the embedded lists only have {}-letter \
+                words; use --words-file to add others)",
+                N,
+                embedded_lengths.join("/")
+            ));
+        }
+        Ok(words)
+    }
+}
+
+/// Building lists from the sources that a `WordListConfig` describes.
+impl<const N: usize> WordList<N> {
+    /// The list from the default `WordListConfig`.
+    pub fn std() -> Result<Self, String> {
+        Self::std_with_limit(WordListConfig::default().limit)
+    }
+
+    /// Like `std()`, but with up to `limit` words instead of the standard size. Fails if the
+    /// embedded lists don't have any `N`-letter words.
+    pub fn std_with_limit(limit: usize) -> Result<Self, String> {
+        WordListConfig {
+            limit,
+            ..WordListConfig::default()
+        }
+        .load()
+    }
+
+    pub fn combine<I>(items: I, limit: usize, normalize: Normalize) -> Self
+    where
+        I: Iterator<Item = (Self, f64)>,
+    {
+        // There may be a more clever way to do this, in a streaming fashion. But for now, I'm
+        // just going to do the brute-force approach.
+        // let itemsRef = &items;
+        let mut acc: HashMap<String, f64> = HashMap::new();
+        for (word_list, factor) in items {
+            let divisor = match normalize {
+                Normalize::Total => word_list.words().map(|wf| wf.freq).sum(),
+                Normalize::Max => word_list.words().map(|wf| wf.freq).fold(0.0, f64::max),
+                Normalize::Rank | Normalize::None => 1.0,
+            };
+            for (rank, word_freq) in word_list.words().enumerate() {
+                let freq = match normalize {
+                    Normalize::Rank => 1.0 / (rank + 1) as f64,
+                    _ if divisor == 0.0 => 0.0,
+                    _ => word_freq.freq / divisor,
+                };
+                let entry = acc.entry(word_freq.word.clone());
+                *entry.or_insert(0.0) += freq * factor;
+            }
+        }
+        // We could be more efficient with this: rather than coming up with the full list, and
+        // then trimming it, we could inert-and-trim as we go. Not important for now, though.
+        let mut acc_vec: Vec<WordFreq> = acc
+            .into_iter()
+            .map(|(word, freq)| WordFreq { word, freq })
+            .collect();
+        acc_vec.sort_by(|first, second| match second.freq.total_cmp(&first.freq) {
+            Ordering::Equal => first.word.cmp(&second.word),
+            ne => ne,
+        });
+        acc_vec.truncate(limit);
+        // We could compact now, but probably not worth the CPU. It'll get compacted next time
+        // it's filtered, anyway.
+
+        WordList::new(acc_vec)
+    }
+}
+
+impl WordsFile {
+    /// The word lengths that this file has a list for.
+    pub fn lengths(&self) -> Vec<usize> {
+        (1..=MAX_EMBEDDED_LENGTH)
+            .filter(|&len| self.get_file_contents(len).is_some())
+            .collect()
+    }
+
+    /// Returns up to `limit` of this file's `N`-letter words, or an empty list if it doesn't have
+    /// any (see `lengths`).
+    pub fn get_embedded<const N: usize>(&self, limit: usize) -> WordList<N> {
+        let Some(file) = self.get_file_contents(N) else {
+            return WordList::empty();
+        };
+        let mut words = Vec::with_capacity(file.chars().filter(|c| c == &'\n').count());
+        for line in file.split("\n") {
+            let Some((word, freq_str)) = line.split_once("\t") else {
+                continue;
+            };
+            if word.len() != N {
+                continue;
+            }
+            let Ok(freq) = freq_str.parse::<f64>() else {
+                continue;
+            };
+            words.push(WordFreq {
+                word: word.to_ascii_uppercase(),
+                freq,
+            });
+            if words.len() >= limit {
+                break;
+            }
+        }
+        WordList::new(words)
+    }
+}
+
+impl WordsFile {
+    /// The name to use for this file in configs and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WordsFile::WGutenberg => "gutenberg",
+            WordsFile::Norvig => "norvig",
+            WordsFile::HermitDave => "hermitdave",
+        }
+    }
+
+    fn get_file_contents(&self, len: usize) -> Option<&'static str> {
+        match (self, len) {
+            (WordsFile::WGutenberg, 5) => {
+                Some(include_str!("words-5chars-wiktionary-gutenberg.txt"))
+            }
+            (WordsFile::Norvig, 5) => Some(include_str!("words-5chars-norvig.txt")),
+            (WordsFile::HermitDave, 5) => Some(include_str!("words-5chars-hermitdave.txt")),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for WordsFile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WordsFile::iter()
+            .find(|wf| wf.name() == s)
+            .ok_or_else(|| format!("unknown embedded word list: {}", s))
+    }
+}
+
+impl FromStr for Normalize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "total" => Ok(Normalize::Total),
+            "max" => Ok(Normalize::Max),
+            "rank" => Ok(Normalize::Rank),
+            "none" => Ok(Normalize::None),
+            other => Err(format!(
+                "unknown normalization: {} (expected total, max, rank or none)",
+                other
+            )),
+        }
+    }
+}
+
+impl SourceConfig {
+    pub fn embedded(file: WordsFile, weight: f64) -> Self {
+        SourceConfig {
+            source: Source::Embedded(file),
+            weight,
+        }
+    }
+
+    fn load<const N: usize>(&self, limit: usize) -> Result<WordList<N>, String> {
+        match &self.source {
+            // Take more than the limit from each embedded list, since the combined list's order
+            // can be different from any one list's.
+            Source::Embedded(file) => Ok(file.get_embedded(limit.saturating_mul(2))),
+            Source::File { path, format } => load_words_file(path, *format),
+        }
+    }
+}
+
+impl From<WordsFileSpec> for SourceConfig {
+    fn from(spec: WordsFileSpec) -> Self {
+        SourceConfig {
+            source: Source::File {
+                path: spec.path,
+                format: spec.format,
+            },
+            weight: spec.weight,
+        }
+    }
+}
+
+impl FromStr for EmbeddedSources {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, weight) = split_weight(s)?;
+        let sources = match name {
+            "std" => WordListConfig::default()
+                .sources
+                .into_iter()
+                .map(|source| SourceConfig {
+                    weight: source.weight * weight,
+                    ..source
+                })
+                .collect(),
+            "none" => Vec::new(),
+            name => vec![SourceConfig::embedded(name.parse()?, weight)],
+        };
+        Ok(EmbeddedSources(sources))
+    }
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config: WordListConfig = serde_json::from_str(
+            r#"{
+                "sources": [
+                    {"embedded": "gutenberg", "weight": 5},
+                    {"embedded": "hermitdave"},
+                    {"file": {"path": "answers.txt", "format": "answers"}, "weight": 2}
+                ],
+                "normalize": "rank"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            WordListConfig {
+                sources: vec![
+                    SourceConfig::embedded(WordsFile::WGutenberg, 5.0),
+                    SourceConfig::embedded(WordsFile::HermitDave, 1.0),
+                    SourceConfig {
+                        source: Source::File {
+                            path: PathBuf::from("answers.txt"),
+                            format: Some(WordsFileFormat::Answers),
+                        },
+                        weight: 2.0,
+                    },
+                ],
+                limit: 7_500,
+                normalize: Normalize::Rank,
+            },
+            config
+        );
+
+        assert!(serde_json::from_str::<WordListConfig>(r#"{"limt": 10}"#).is_err());
+        assert!(
+            serde_json::from_str::<WordListConfig>(r#"{"sources": [{"embedded": "nope"}]}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn embedded_sources() {
+        let EmbeddedSources(std) = "std=2".parse().unwrap();
+        let weights: Vec<f64> = std.iter().map(|s| s.weight).collect();
+        assert_eq!(vec![10.0, 0.0, 2.0], weights);
+
+        assert_eq!(
+            EmbeddedSources(vec![SourceConfig::embedded(WordsFile::Norvig, 1.0)]),
+            "norvig".parse().unwrap()
+        );
+        assert_eq!(EmbeddedSources(Vec::new()), "none".parse().unwrap());
+        assert!("webster".parse::<EmbeddedSources>().is_err());
+    }
+
+    #[test]
+    fn set_embedded_keeps_files() {
+        let mut config = WordListConfig::default();
+        config
+            .sources
+            .push("answers:a.txt=2".parse::<WordsFileSpec>().unwrap().into());
+        config.set_embedded(vec![SourceConfig::embedded(WordsFile::Norvig, 3.0)]);
+        assert_eq!(2, config.sources.len());
+        assert_eq!(
+            SourceConfig::embedded(WordsFile::Norvig, 3.0),
+            config.sources[0]
+        );
+        assert_eq!(2.0, config.sources[1].weight);
+    }
+
+    #[test]
+    fn normalization() {
+        let config = WordListConfig {
+            sources: vec![SourceConfig::embedded(WordsFile::HermitDave, 1.0)],
+            limit: 3,
+            normalize: Normalize::Rank,
+        };
+        let words: WordList<5> = config.load().unwrap();
+        let freqs: Vec<f64> = words.words().map(|w| w.freq).collect();
+        assert_eq!(vec![1.0, 0.5, 1.0 / 3.0], freqs);
+
+        let config = WordListConfig {
+            normalize: Normalize::Max,
+            ..config
+        };
+        let words: WordList<5> = config.load().unwrap();
+        assert_eq!(Some(1.0), words.words().next().map(|w| w.freq));
+    }
+
+    #[test]
+    fn other_lengths() {
+        assert_eq!(vec![5], WordsFile::Norvig.lengths());
+
+        let Err(err) = WordListConfig::default().load::<6>() else {
+            panic!("the embedded lists shouldn't have 6-letter words");
+        };
+        assert!(err.contains("--words-file"), "{}", err);
+
+        let path = std::env::temp_dir().join(format!("wordlehelper-words6-{}", std::process::id()));
+        fs::write(&path, "planet\nstairs\nrover\n").unwrap();
+        let mut config = WordListConfig::default();
+        config.sources.push(SourceConfig {
+            source: Source::File {
+                path: path.clone(),
+                format: Some(WordsFileFormat::Plain),
+            },
+            weight: 1.0,
+        });
+        let words = config.load::<6>();
+        fs::remove_file(&path).unwrap();
+        let words: Vec<String> = words.unwrap().words().map(|w| w.word.clone()).collect();
+        assert_eq!(vec!["PLANET", "STAIRS"], words);
+    }
+}
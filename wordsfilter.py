@@ -2,7 +2,10 @@
 '''
 Reads stdin, and assumes the lines are all of form <word><tab><frequency>
 
-It then outputs all the lines within that which (a) are 5 letters, and (b) do NOT have a corresponding 4-letter word plus an "s".
+It then outputs all the lines within that which (a) are N letters, and (b) do NOT have a corresponding (N-1)-letter word plus
+an "s". N is the first argument, and defaults to 5; so for example, the 6-letter Norvig list comes from:
+
+    ./wordsfilter.py 6 < count_1w.txt > src/words-6chars-norvig.txt
 
 For example, if the file contained:
 
@@ -15,15 +18,17 @@ For example, if the file contained:
     three	123
 
 The "four 456" line is omitted because it doesn't have 5 chars, and the "fours 123" line is omitted because "fours" == "four" + "s".
+(That's with N = 5.)
 '''
 
 import sys
 
-word_5char_freqs = {}
-words_4char_with_s = set()
-words_6char_with_s = {}
-words_7char_with_es = {}
-words_8char_with_ies = {}
+word_len_wanted = int(sys.argv[1]) if len(sys.argv) > 1 else 5
+
+word_freqs = {}
+shorter_words_with_s = set()
+longer_words_with_s = {}
+longer_words_with_es = {}
 
 
 def add_to(m, k, v):
@@ -47,24 +52,25 @@ for line_no, line in enumerate(sys.stdin):
     if not word.isalpha():
         continue
     word_len = len(word)
-    if word_len == 5:
-        add_to(word_5char_freqs, word, freq)
-    elif word_len == 4:
-        words_4char_with_s.add(word + 's')
-    elif word_len == 6 and word.endswith('s'):
-        add_to(words_6char_with_s, word, freq)
-    elif word_len == 7 and word.endswith('es'):
-        add_to(words_7char_with_es, word, freq)
+    if word_len == word_len_wanted:
+        add_to(word_freqs, word, freq)
+    elif word_len == word_len_wanted - 1:
+        shorter_words_with_s.add(word + 's')
+    elif word_len == word_len_wanted + 1 and word.endswith('s'):
+        add_to(longer_words_with_s, word, freq)
+    elif word_len == word_len_wanted + 2 and word.endswith('es'):
+        # This includes the "ies" plurals, like "berries" for "berry".
+        add_to(longer_words_with_es, word, freq)
 
 # now copy them over to a [(word, freq)], editing as you go
 out_pairs = []
-for word, freq in word_5char_freqs.items():
-    if word in words_4char_with_s:
+for word, freq in word_freqs.items():
+    if word in shorter_words_with_s:
         continue
-    freq += find_plural(words_6char_with_s, word + 's')
-    freq += find_plural(words_7char_with_es, word + 'es')
+    freq += find_plural(longer_words_with_s, word + 's')
+    freq += find_plural(longer_words_with_es, word + 'es')
     if word.endswith('y'):
-        freq += find_plural(words_8char_with_ies, word[:-1] + 'ies')
+        freq += find_plural(longer_words_with_es, word[:-1] + 'ies')
     out_pairs.append((word, freq))
 
 out_pairs.sort(key=lambda x: x[1], reverse=True)